@@ -1,4 +1,41 @@
-use crate::{Entry, EntryList};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    time::{Duration, SystemTime},
+};
+
+use uuid::Uuid;
+
+use crate::{entries::resolve_alias, format_iso_week, Entry, EntryList};
+
+/// Gets the moment a local date and time happens, or happens an hour later if it was skipped by a
+/// daylight saving change
+pub fn local_time(naive: NaiveDateTime) -> SystemTime {
+    [naive, naive + chrono::Duration::hours(1)]
+        .iter()
+        .find_map(|naive| naive.and_local_timezone(Local).earliest())
+        .expect("Daylight saving changes skip at most an hour")
+        .into()
+}
+
+/// Gets the span of time covered by the given local day, from its midnight to the next
+///
+/// In time zones where a daylight saving change skips midnight, the day starts when the clocks go
+/// forward instead.
+pub fn day_range(day: NaiveDate) -> (SystemTime, SystemTime) {
+    let midnight = |day: NaiveDate| local_time(day.and_time(NaiveTime::MIN));
+
+    (midnight(day), midnight(day + Days::new(1)))
+}
+
+/// Which days (or weeks) tracked time is spread over when taking an average
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AverageBasis {
+    /// Only the days or weeks in which some entry started
+    Active,
+    /// Every day or week in the range, whether or not anything was tracked
+    Calendar,
+}
 
 impl EntryList {
     /// Returns all the entries in this list, sorted by their `start_time`
@@ -12,4 +49,744 @@ impl EntryList {
 
         entries
     }
+
+    /// Returns copies of all the entries in this list, sorted by their `start_time`
+    ///
+    /// Unlike [`EntryList::get_entries_in_order`], the result doesn't borrow the list, so it can be
+    /// sent to other threads or outlive it. This clones every entry (including its strings), so prefer
+    /// the borrowing version when that's not needed.
+    pub fn get_entries_owned(&self, ascending: bool) -> Vec<Entry> {
+        self.get_entries_in_order(ascending)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the `n` most recently finished entries, most recent first
+    ///
+    /// Returns fewer if there aren't that many finished entries.
+    pub fn last_completed_n(&self, n: usize) -> Vec<&Entry> {
+        let mut entries: Vec<_> = self
+            .entries
+            .values()
+            .filter(|entry| entry.end_time().is_some())
+            .collect();
+        entries.sort_unstable_by_key(|entry| std::cmp::Reverse(entry.end_time()));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Returns the entries which started within `[from, to)`, sorted by their `start_time`
+    ///
+    /// If the range extends up to now, the running entry is included even if it started before `from`.
+    pub fn get_entries_in_range(
+        &self,
+        from: SystemTime,
+        to: SystemTime,
+        ascending: bool,
+    ) -> Vec<&Entry> {
+        let now = SystemTime::now();
+        let mut entries = self.get_entries_in_order(ascending);
+
+        entries.retain(|entry| {
+            let started_in_range = from <= entry.start_time() && entry.start_time() < to;
+            let running_into_range = entry.end_time().is_none() && to >= now;
+
+            started_in_range || running_into_range
+        });
+
+        entries
+    }
+
+    /// Finds the longest continuous block of tracked time
+    ///
+    /// Entries separated by gaps of at most `max_gap` (or overlapping) are coalesced into a single
+    /// block, and the longest block is returned as its start, end and length. The running entry is
+    /// treated as ending now. Returns `None` if there are no entries.
+    pub fn longest_focus_block(
+        &self,
+        max_gap: Duration,
+    ) -> Option<(SystemTime, SystemTime, Duration)> {
+        let now = SystemTime::now();
+        let mut longest: Option<(SystemTime, SystemTime, Duration)> = None;
+        let mut block: Option<(SystemTime, SystemTime)> = None;
+
+        for entry in self.get_entries_in_order(true) {
+            let start = entry.start_time();
+            let end = entry.end_time().unwrap_or(now).max(start);
+
+            block = match block {
+                Some((block_start, block_end)) if start <= block_end + max_gap => {
+                    Some((block_start, block_end.max(end)))
+                }
+                _ => Some((start, end)),
+            };
+
+            if let Some((block_start, block_end)) = block {
+                let length = block_end.duration_since(block_start).unwrap_or_default();
+                if longest.is_none_or(|(_, _, longest_length)| length > longest_length) {
+                    longest = Some((block_start, block_end, length));
+                }
+            }
+        }
+
+        longest
+    }
+
+    /// Finds every pair of entries whose `[start, end)` intervals intersect
+    ///
+    /// The running entry is treated as ending now, and entries lasting no time at all never overlap
+    /// anything. Each pair is given once, with the entry that started first (or the lower id, on a
+    /// tie) first.
+    pub fn find_overlaps(&self) -> Vec<(Uuid, Uuid)> {
+        let now = SystemTime::now();
+        let mut entries: Vec<&Entry> = self.entries.values().collect();
+        entries.sort_by_key(|entry| (entry.start_time(), entry.id()));
+
+        // Entries which started earlier and haven't ended yet, as of the start being looked at
+        let mut open: Vec<(SystemTime, Uuid)> = Vec::new();
+        let mut overlaps = Vec::new();
+        for entry in entries {
+            let start = entry.start_time();
+            let end = entry.end_time().unwrap_or(now);
+            if end <= start {
+                continue;
+            }
+
+            open.retain(|(open_end, _)| *open_end > start);
+            overlaps.extend(open.iter().map(|(_, id)| (*id, entry.uuid())));
+            open.push((end, entry.uuid()));
+        }
+
+        overlaps
+    }
+
+    /// Finds the stretches of `[from, to)` not covered by any entry, which are longer than `min_gap`
+    ///
+    /// Overlapping entries are treated as one, and the running entry is treated as ending now. Time
+    /// after now is never counted as a gap, since it can't have been tracked yet. Gaps are given as
+    /// `(start, end)`, earliest first.
+    pub fn gaps_in_range(
+        &self,
+        from: SystemTime,
+        to: SystemTime,
+        min_gap: Duration,
+    ) -> Vec<(SystemTime, SystemTime)> {
+        let now = SystemTime::now();
+        let to = to.min(now);
+        let mut gaps = Vec::new();
+
+        // Everything before this is covered, or has already been given as a gap
+        let mut covered_until = from;
+        for entry in self.get_entries_in_order(true) {
+            if covered_until >= to {
+                break;
+            }
+            let start = entry.start_time();
+            let end = entry.end_time().unwrap_or(now);
+            if end <= covered_until {
+                continue;
+            }
+
+            if start > covered_until {
+                gaps.push((covered_until, start.min(to)));
+            }
+            covered_until = covered_until.max(end);
+        }
+        if covered_until < to {
+            gaps.push((covered_until, to));
+        }
+
+        gaps.retain(|(start, end)| end.duration_since(*start).unwrap_or_default() > min_gap);
+        gaps
+    }
+}
+
+impl EntryList {
+    /// Sums the durations of all finished entries, grouped by their project as stored
+    ///
+    /// Entries without a project are totalled under `None`. Unlike [`EntryList::total_duration_by_project`]
+    /// there's no range, labelling or estimate filtering; every finished entry counts, unless
+    /// [only billable ones](EntryList::set_billable_only) are.
+    pub fn project_totals(&self) -> HashMap<Option<String>, Duration> {
+        let mut totals = HashMap::new();
+
+        for entry in self.entries.values() {
+            if !self.counts_for_billing(entry) {
+                continue;
+            }
+            if let Some(duration) = entry.duration() {
+                *totals.entry(entry.project().clone()).or_default() += duration;
+            }
+        }
+
+        totals
+    }
+
+    /// Sums the durations of finished entries which started within `[since, until)`, grouped by project
+    ///
+    /// Entries without a project are grouped under [`EntryList::no_project_label`]. Running entries
+    /// are not counted, and neither are [estimated](Entry::estimated) ones unless `include_estimated` is set.
+    pub fn total_duration_by_project(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(
+            since,
+            until,
+            include_estimated,
+            |entry| self.project_label(entry).to_string(),
+            Entry::duration,
+        )
+    }
+
+    /// Sums the durations of finished entries which started within `[since, until)`, grouped by category
+    ///
+    /// Entries without a category are grouped under [`EntryList::category_label`]. Running entries are not
+    /// counted, and neither are [estimated](Entry::estimated) ones unless `include_estimated` is set.
+    pub fn total_duration_by_category(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(
+            since,
+            until,
+            include_estimated,
+            |entry| self.category_label(entry).to_string(),
+            Entry::duration,
+        )
+    }
+
+    /// Sums the durations of all finished entries, grouped by tag
+    ///
+    /// An entry counts fully towards each of its tags, so these totals can add up to more than the
+    /// time actually tracked. Tags are folded into others following [`EntryList::set_tag_aliases`],
+    /// and entries without tags aren't counted. Like [`EntryList::project_totals`], there's no range
+    /// or estimate filtering.
+    pub fn tag_totals(&self) -> HashMap<String, Duration> {
+        let mut totals = HashMap::new();
+
+        for entry in self.entries.values() {
+            if !self.counts_for_billing(entry) {
+                continue;
+            }
+            if let Some(duration) = entry.duration() {
+                for tag in self.tag_labels(entry) {
+                    *totals.entry(tag).or_default() += duration;
+                }
+            }
+        }
+
+        totals
+    }
+
+    /// Sums the durations of finished entries which started within `[since, until)`, grouped by tag
+    ///
+    /// As in [`EntryList::tag_totals`], an entry counts fully towards each of its tags, so the totals
+    /// can add up to more than the time tracked. Otherwise entries are counted as in
+    /// [`EntryList::total_duration_by_project`].
+    pub fn total_duration_by_tag(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> HashMap<String, Duration> {
+        self.totals_by_each(
+            since,
+            until,
+            include_estimated,
+            |entry| self.tag_labels(entry),
+            Entry::duration,
+        )
+    }
+
+    /// Gets the distinct tags an entry is grouped under, following [`EntryList::set_tag_aliases`]
+    pub fn tag_labels(&self, entry: &Entry) -> Vec<String> {
+        let tags: BTreeSet<_> = entry.tags().iter().map(|tag| self.tag_label(tag)).collect();
+        tags.into_iter().map(str::to_string).collect()
+    }
+
+    /// Sums the durations of finished entries which started within `[since, until)`, grouped by the
+    /// ISO week they started in
+    ///
+    /// Weeks are labelled like `2024-W03` (see [`format_iso_week`]), so they sort in order. Entries
+    /// are counted as in [`EntryList::total_duration_by_project`].
+    pub fn total_duration_by_week(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(since, until, include_estimated, week_label, Entry::duration)
+    }
+
+    /// Sums `measure` over entries which started within `[since, until)`, grouped by `label`
+    ///
+    /// Entries `measure` gives nothing for are skipped, as are [estimated](Entry::estimated) ones
+    /// unless `include_estimated` is set.
+    pub(crate) fn totals_by(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        label: impl Fn(&Entry) -> String,
+        measure: impl Fn(&Entry) -> Option<Duration>,
+    ) -> HashMap<String, Duration> {
+        self.totals_by_each(
+            since,
+            until,
+            include_estimated,
+            |entry| vec![label(entry)],
+            measure,
+        )
+    }
+
+    /// Like [`EntryList::totals_by`], but each entry counts fully towards every one of its `labels`
+    pub(crate) fn totals_by_each(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        labels: impl Fn(&Entry) -> Vec<String>,
+        measure: impl Fn(&Entry) -> Option<Duration>,
+    ) -> HashMap<String, Duration> {
+        let mut totals = HashMap::new();
+
+        for entry in self.get_entries_in_range(since, until, true) {
+            if (entry.estimated() && !include_estimated) || !self.counts_for_billing(entry) {
+                continue;
+            }
+            if let Some(duration) = measure(entry) {
+                for label in labels(entry) {
+                    *totals.entry(label).or_default() += duration;
+                }
+            }
+        }
+
+        totals
+    }
+
+    /// Ranks projects by the time spent on them within `[since, until)`, most time first
+    ///
+    /// Each project comes with its total duration and its percentage (0 to 100) of the time across
+    /// all projects. Ties are broken by project name. See [`EntryList::total_duration_by_project`]
+    /// for which entries are counted.
+    pub fn ranked_projects(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> Vec<(String, Duration, f64)> {
+        rank(self.total_duration_by_project(since, until, include_estimated))
+    }
+
+    /// Ranks categories by the time spent on them within `[since, until)`, most time first
+    ///
+    /// Like [`EntryList::ranked_projects`], but grouped as in [`EntryList::total_duration_by_category`].
+    pub fn ranked_categories(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+    ) -> Vec<(String, Duration, f64)> {
+        rank(self.total_duration_by_category(since, until, include_estimated))
+    }
+}
+
+impl EntryList {
+    /// Gets the total time tracked on each local day, keyed by the day each entry started on
+    ///
+    /// Entries are selected as in [`EntryList::get_entries_in_range`]. If `include_running` is set,
+    /// the running entry counts its time so far; otherwise only finished entries are totalled. Days
+    /// with nothing tracked are left out.
+    pub fn daily_totals(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_running: bool,
+    ) -> BTreeMap<NaiveDate, Duration> {
+        let mut totals = BTreeMap::new();
+        for entry in self.get_entries_in_range(since, until, true) {
+            let duration = match entry.duration() {
+                Some(duration) => duration,
+                None if include_running => entry.elapsed(),
+                None => continue,
+            };
+
+            let day = DateTime::<Local>::from(entry.start_time()).date_naive();
+            *totals.entry(day).or_default() += duration;
+        }
+
+        totals
+    }
+
+    /// Sums the durations of all finished entries, keyed by the ISO year and week they started in
+    ///
+    /// Weeks start on Monday, as in ISO 8601, and are worked out in local time. Like
+    /// [`EntryList::project_totals`], there's no range or estimate filtering, but only billable
+    /// entries count when [only billable ones](EntryList::set_billable_only) do. Weeks with nothing
+    /// tracked are left out.
+    pub fn weekly_totals(&self) -> BTreeMap<(i32, u32), Duration> {
+        let mut totals = BTreeMap::new();
+        for entry in self.entries.values() {
+            if !self.counts_for_billing(entry) {
+                continue;
+            }
+            if let Some(duration) = entry.duration() {
+                *totals.entry(entry.iso_week()).or_default() += duration;
+            }
+        }
+
+        totals
+    }
+
+    /// Gets the average time tracked per local day within `[since, until)`
+    ///
+    /// Finished entries which started in the range are totalled, then divided by the number of days
+    /// given by `basis`. Returns zero if there's nothing to divide by.
+    pub fn average_per_day(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        basis: AverageBasis,
+    ) -> Duration {
+        self.average_per_period(since, until, basis, 1, |day| day)
+    }
+
+    /// Gets the average time tracked per ISO week (starting Monday) within `[since, until)`
+    ///
+    /// Like [`EntryList::average_per_day`], but divided by a number of weeks.
+    pub fn average_per_week(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        basis: AverageBasis,
+    ) -> Duration {
+        self.average_per_period(since, until, basis, 7, |day| {
+            day - Days::new(day.weekday().num_days_from_monday().into())
+        })
+    }
+
+    /// Averages tracked time over periods of `period_days` days, where `period_start` maps a day to
+    /// the first day of its period
+    fn average_per_period(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        basis: AverageBasis,
+        period_days: i64,
+        period_start: impl Fn(NaiveDate) -> NaiveDate,
+    ) -> Duration {
+        let local_day = |time: SystemTime| DateTime::<Local>::from(time).date_naive();
+
+        let mut total = Duration::ZERO;
+        let mut active = HashSet::new();
+        for entry in self.get_entries_in_range(since, until, true) {
+            if let Some(duration) = entry.duration() {
+                total += duration;
+                active.insert(period_start(local_day(entry.start_time())));
+            }
+        }
+
+        let periods = match basis {
+            AverageBasis::Active => active.len() as u32,
+            AverageBasis::Calendar if since < until => {
+                // The range is half open, so its last day is the one just before `until`
+                let last = until - Duration::from_nanos(1);
+                let span = period_start(local_day(last)) - period_start(local_day(since));
+                (span.num_days() / period_days + 1) as u32
+            }
+            AverageBasis::Calendar => 0,
+        };
+
+        total.checked_div(periods).unwrap_or_default()
+    }
+}
+
+impl EntryList {
+    /// Returns every entry linked to the given one through [`Entry::continued_from`], sorted by their `start_time`
+    ///
+    /// The chain is followed back to the entry that started the task, then forward through every
+    /// entry continuing it, so any entry in the chain gives the same result. The given entry is
+    /// included. Returns an empty list if there's no entry with that id.
+    pub fn chain_for(&self, id: Uuid) -> Vec<&Entry> {
+        let mut root = match self.entries.get(id.as_bytes()) {
+            Some(entry) => entry,
+            None => return Vec::new(),
+        };
+
+        // Links are edited by hand sometimes, so guard against going round in circles
+        let mut visited = HashSet::from([root.id()]);
+        while let Some(previous) = root.continued_from.and_then(|id| self.entries.get(&id)) {
+            if !visited.insert(previous.id()) {
+                break;
+            }
+            root = previous;
+        }
+
+        let mut chain = vec![root];
+        let mut seen = HashSet::from([root.id()]);
+        let mut next = 0;
+        while let Some(&entry) = chain.get(next) {
+            chain.extend(self.entries.values().filter(|candidate| {
+                candidate.continued_from == Some(entry.id()) && seen.insert(candidate.id())
+            }));
+            next += 1;
+        }
+
+        chain.sort_unstable();
+        chain
+    }
+}
+
+/// Adds up the durations of some entries, e.g. those left after filtering a log
+///
+/// Running entries count for their time so far if `include_running` is set, and are left out
+/// otherwise.
+pub fn total_of(entries: &[&Entry], include_running: bool) -> Duration {
+    entries
+        .iter()
+        .filter_map(|entry| match entry.duration() {
+            Some(duration) => Some(duration),
+            None => include_running.then(|| entry.elapsed()),
+        })
+        .sum()
+}
+
+/// Keeps one page of some sorted entries, skipping the first `offset` and keeping at most `limit`
+/// of the rest (or all of them, if there's no limit)
+pub fn page<'a>(entries: &[&'a Entry], offset: usize, limit: Option<usize>) -> Vec<&'a Entry> {
+    let rest = entries.iter().skip(offset).copied();
+    match limit {
+        Some(limit) => rest.take(limit).collect(),
+        None => rest.collect(),
+    }
+}
+
+/// Sorts totals by duration (most first, ties broken by name), adding each one's percentage of the overall total
+///
+/// This is how [`EntryList::ranked_projects`] and [`EntryList::ranked_categories`] rank their totals,
+/// for ranking totals which have been adjusted first.
+pub fn rank(totals: HashMap<String, Duration>) -> Vec<(String, Duration, f64)> {
+    let overall: Duration = totals.values().sum();
+
+    let mut ranked: Vec<_> = totals
+        .into_iter()
+        .map(|(name, duration)| {
+            let percentage = if overall.is_zero() {
+                0.0
+            } else {
+                duration.as_secs_f64() / overall.as_secs_f64() * 100.0
+            };
+            (name, duration, percentage)
+        })
+        .collect();
+    ranked.sort_unstable_by(|(a_name, a_duration, _), (b_name, b_duration, _)| {
+        b_duration.cmp(a_duration).then_with(|| a_name.cmp(b_name))
+    });
+
+    ranked
+}
+
+/// Labels an entry with the ISO week it started in, for [`EntryList::total_duration_by_week`]
+pub(crate) fn week_label(entry: &Entry) -> String {
+    let (year, week) = entry.iso_week();
+    format_iso_week(year, week)
+}
+
+impl Entry {
+    /// Get the ISO year and week (starting Monday) this entry started in, in local time
+    ///
+    /// Near New Year the ISO year can differ from the calendar year, e.g. 2024-12-30 is in week 1
+    /// of 2025.
+    pub fn iso_week(&self) -> (i32, u32) {
+        let week = DateTime::<Local>::from(self.start_time()).iso_week();
+        (week.year(), week.week())
+    }
+
+    /// Checks whether this entry's [elapsed](Entry::elapsed) time is within the given bounds, inclusive
+    pub fn duration_within(&self, min: Option<Duration>, max: Option<Duration>) -> bool {
+        let elapsed = self.elapsed();
+        min.is_none_or(|min| elapsed >= min) && max.is_none_or(|max| elapsed <= max)
+    }
+
+    /// Checks whether this entry's project matches the given pattern
+    ///
+    /// Patterns without wildcards must match the project exactly. Otherwise, `*` matches any run of
+    /// characters and `?` matches any single character, so `Client/*` matches every project under
+    /// `Client/`. Entries without a project never match.
+    pub fn project_matches(&self, pattern: &str) -> bool {
+        match self.project() {
+            Some(project) if is_glob(pattern) => glob_matches(pattern, project),
+            Some(project) => project == pattern,
+            None => false,
+        }
+    }
+}
+
+impl EntryList {
+    /// Returns the entries whose project is exactly `project`, sorted by their `start_time`
+    ///
+    /// Entries without a project never match. See [`EntryList::entries_for_project_matching`] for
+    /// wildcard patterns.
+    pub fn get_entries_by_project(&self, project: &str, ascending: bool) -> Vec<&Entry> {
+        let mut entries = self.get_entries_in_order(ascending);
+        entries.retain(|entry| entry.project().as_deref() == Some(project));
+        entries
+    }
+
+    /// Returns the entries tagged with exactly `tag` (case-sensitive), sorted by their `start_time`
+    pub fn get_entries_by_tag(&self, tag: &str, ascending: bool) -> Vec<&Entry> {
+        let mut entries = self.get_entries_in_order(ascending);
+        entries.retain(|entry| entry.tags().iter().any(|entry_tag| entry_tag == tag));
+        entries
+    }
+
+    /// Returns the entries whose project matches the given pattern, sorted by their `start_time`
+    ///
+    /// See [`Entry::project_matches`] for the pattern syntax.
+    pub fn entries_for_project_matching(&self, pattern: &str) -> Vec<&Entry> {
+        let mut entries = self.get_entries_in_order(true);
+        entries.retain(|entry| entry.project_matches(pattern));
+        entries
+    }
+
+    /// Returns the entries whose description or project contains `query`, ignoring case, newest first
+    ///
+    /// Entries without a description or project just don't match on that field.
+    pub fn search(&self, query: &str) -> Vec<&Entry> {
+        let query = query.to_lowercase();
+        let contains = |field: &Option<String>| {
+            field
+                .as_deref()
+                .is_some_and(|text| text.to_lowercase().contains(&query))
+        };
+
+        let mut entries = self.get_entries_in_order(false);
+        entries.retain(|entry| contains(entry.description()) || contains(entry.project()));
+        entries
+    }
+
+    /// Returns every project used by any entry, sorted and without duplicates
+    ///
+    /// Entries without a project (or with an empty one) contribute nothing.
+    pub fn all_projects(&self) -> Vec<String> {
+        let projects: BTreeSet<_> = self
+            .entries
+            .values()
+            .filter_map(|entry| entry.project().as_deref())
+            .filter(|project| !project.is_empty())
+            .collect();
+        projects.into_iter().map(str::to_string).collect()
+    }
+
+    /// Returns every tag used by any entry exactly as stored, sorted and without duplicates
+    ///
+    /// Unlike [`EntryList::tag_histogram_with_aliases`], aliases aren't folded together, so
+    /// near-duplicates like `clientA` and `client-a` both show up. Empty tags are skipped.
+    pub fn all_tags(&self) -> Vec<String> {
+        let tags: BTreeSet<_> = self
+            .entries
+            .values()
+            .flat_map(|entry| entry.tags())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.into_iter().cloned().collect()
+    }
+
+    /// Counts how many entries have each tag, counting tags listed in `aliases` as the tag they map to
+    ///
+    /// An entry with several tags which fold into the same one (e.g. both `mtg` and `meeting`) is
+    /// only counted once for it. Stored tags are left untouched.
+    pub fn tag_histogram_with_aliases(
+        &self,
+        aliases: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for entry in self.entries.values() {
+            let tags: BTreeSet<_> = entry
+                .tags()
+                .iter()
+                .map(|tag| resolve_alias(aliases, tag))
+                .collect();
+            for tag in tags {
+                *histogram.entry(tag.to_string()).or_default() += 1;
+            }
+        }
+
+        histogram
+    }
+
+    /// Returns the entries lasting at least `min` and at most `max`, sorted by their `start_time`
+    ///
+    /// Either bound can be left out. Running entries are measured up to now.
+    pub fn entries_with_duration(
+        &self,
+        min: Option<Duration>,
+        max: Option<Duration>,
+    ) -> Vec<&Entry> {
+        let mut entries = self.get_entries_in_order(true);
+        entries.retain(|entry| entry.duration_within(min, max));
+        entries
+    }
+
+    /// Returns the distinct, non-empty descriptions used for the given project, most recently used first
+    ///
+    /// See [`Entry::project_matches`] for the pattern syntax.
+    pub fn descriptions_for_project(&self, project: &str) -> Vec<String> {
+        let mut seen = HashSet::new();
+
+        self.get_entries_in_order(false)
+            .into_iter()
+            .filter(|entry| entry.project_matches(project))
+            .filter_map(|entry| entry.description().as_deref())
+            .filter(|description| !description.trim().is_empty())
+            .filter(|description| seen.insert(*description))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Checks whether a pattern contains any wildcards
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Matches `text` against a pattern where `*` matches any run of characters and `?` matches any single character
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen in the pattern, and the text position it's currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and try again
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    t = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
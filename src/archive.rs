@@ -0,0 +1,60 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{errors::Result, EntryList, Error};
+
+impl EntryList {
+    /// Moves finished entries which ended at or before `cutoff` into the archive file at the path
+    /// provided, so the active list stays small
+    ///
+    /// The archive is an ordinary data file, so it can be browsed with `--file`. If it already
+    /// exists, the entries are added to it. The running entry, and anything ending after the
+    /// cutoff, stays where it is. The archive is saved straight away, but this list isn't, so a
+    /// crash before it's saved leaves entries in both files rather than losing them.
+    /// Returns the number of entries moved
+    ///
+    /// # Errors
+    /// - Returns an error if this list was opened with [`EntryList::load_readonly`]
+    /// - Returns an error if the archive exists but can't be loaded
+    /// - Returns an error if anything goes wrong writing the archive
+    pub fn archive_before<P>(&mut self, cutoff: SystemTime, archive_path: P) -> Result<usize>
+    where
+        P: AsRef<Path>,
+    {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        let ids: Vec<_> = self
+            .entries
+            .values()
+            .filter(|entry| entry.end_time().is_some_and(|end_time| end_time <= cutoff))
+            .map(|entry| entry.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut archive = EntryList::load_or_create(&archive_path)?;
+        for id in &ids {
+            archive.entries.insert(*id, self.entries[id].clone());
+        }
+        archive.save(&archive_path)?;
+
+        for id in &ids {
+            self.entries.remove(id);
+        }
+        Ok(ids.len())
+    }
+}
+
+/// Gets the default archive for a data file, next to it with `.archive.ron` in place of its
+/// extension, e.g. `entries.archive.ron`
+pub fn archive_path<P>(path: P) -> PathBuf
+where
+    P: AsRef<Path>,
+{
+    path.as_ref().with_extension("archive.ron")
+}
@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use crate::{aggregate::week_label, Entry, EntryList};
+
+/// Label used in place of a currency code for entries with no currency (and no default currency)
+pub const NO_CURRENCY_LABEL: &str = "(no currency)";
+
+/// Which way [`round_duration`] rounds a duration that isn't already a multiple of the increment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundMode {
+    /// Round up to the next multiple, e.g. for billing every started block
+    #[default]
+    Up,
+    /// Round down to the previous multiple
+    Down,
+    /// Round to the closest multiple, with durations exactly halfway rounding up
+    Nearest,
+}
+
+/// Works out what a duration earns at an hourly rate
+///
+/// Round the duration first if billing is rounded.
+pub fn earnings(duration: Duration, hourly_rate: f64) -> f64 {
+    duration.as_secs_f64() / 3600.0 * hourly_rate
+}
+
+/// Rounds a duration to a whole multiple of `increment`
+///
+/// A zero increment leaves the duration as it is.
+pub fn round_duration(duration: Duration, increment: Duration, mode: RoundMode) -> Duration {
+    let step = increment.as_nanos();
+    if step == 0 {
+        return duration;
+    }
+
+    let nanos = duration.as_nanos();
+    let steps = match mode {
+        RoundMode::Up => nanos.div_ceil(step),
+        RoundMode::Down => nanos / step,
+        RoundMode::Nearest if nanos % step * 2 >= step => nanos / step + 1,
+        RoundMode::Nearest => nanos / step,
+    };
+
+    Duration::from_nanos((steps * step) as u64)
+}
+
+/// Parses a rounding increment like `15m`, `1h` or `90s`, where a bare number means minutes
+///
+/// The error is a message meant for the user, e.g. as a command line argument parser.
+pub fn parse_increment(text: &str) -> Result<Duration, String> {
+    let (number, unit_secs) = match text.char_indices().last() {
+        Some((index, 's')) => (&text[..index], 1),
+        Some((index, 'm')) => (&text[..index], 60),
+        Some((index, 'h')) => (&text[..index], 3600),
+        _ => (text, 60),
+    };
+
+    match number
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(unit_secs))
+    {
+        Some(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+        _ => Err(format!(
+            "expected a positive length like \"15m\", \"1h\" or \"90s\", got \"{}\"",
+            text
+        )),
+    }
+}
+
+impl Entry {
+    /// Get the length of this entry rounded to a multiple of `increment`, if it's finished
+    ///
+    /// See [`round_duration`].
+    pub fn rounded_duration(&self, increment: Duration, mode: RoundMode) -> Option<Duration> {
+        self.duration()
+            .map(|duration| round_duration(duration, increment, mode))
+    }
+}
+
+impl EntryList {
+    /// Like [`EntryList::total_duration_by_project`], but with each entry's duration rounded first
+    ///
+    /// See [`Entry::rounded_duration`].
+    pub fn rounded_total_duration_by_project(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        increment: Duration,
+        mode: RoundMode,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(
+            since,
+            until,
+            include_estimated,
+            |entry| self.project_label(entry).to_string(),
+            |entry| entry.rounded_duration(increment, mode),
+        )
+    }
+
+    /// Like [`EntryList::total_duration_by_category`], but with each entry's duration rounded first
+    ///
+    /// See [`Entry::rounded_duration`].
+    pub fn rounded_total_duration_by_category(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        increment: Duration,
+        mode: RoundMode,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(
+            since,
+            until,
+            include_estimated,
+            |entry| self.category_label(entry).to_string(),
+            |entry| entry.rounded_duration(increment, mode),
+        )
+    }
+
+    /// Like [`EntryList::total_duration_by_week`], but with each entry's duration rounded first
+    ///
+    /// See [`Entry::rounded_duration`].
+    pub fn rounded_total_duration_by_week(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        increment: Duration,
+        mode: RoundMode,
+    ) -> HashMap<String, Duration> {
+        self.totals_by(since, until, include_estimated, week_label, |entry| {
+            entry.rounded_duration(increment, mode)
+        })
+    }
+
+    /// Like [`EntryList::total_duration_by_tag`], but with each entry's duration rounded first
+    ///
+    /// See [`Entry::rounded_duration`].
+    pub fn rounded_total_duration_by_tag(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        include_estimated: bool,
+        increment: Duration,
+        mode: RoundMode,
+    ) -> HashMap<String, Duration> {
+        self.totals_by_each(
+            since,
+            until,
+            include_estimated,
+            |entry| self.tag_labels(entry),
+            |entry| entry.rounded_duration(increment, mode),
+        )
+    }
+
+    /// Totals the amount billed for finished entries which started within `[since, until)`, per currency
+    ///
+    /// Each entry is billed at its duration in hours multiplied by the hourly rate `rate` returns for
+    /// it; entries without a rate aren't billed. Amounts are grouped by [`EntryList::currency_of`],
+    /// since adding up different currencies is meaningless. Entries with no currency at all are
+    /// grouped under [`NO_CURRENCY_LABEL`].
+    pub fn total_billable_amount<F>(
+        &self,
+        since: SystemTime,
+        until: SystemTime,
+        rate: F,
+    ) -> HashMap<String, f64>
+    where
+        F: Fn(&Entry) -> Option<f64>,
+    {
+        let mut totals = HashMap::new();
+
+        for entry in self.get_entries_in_range(since, until, true) {
+            if let (Some(duration), Some(rate)) = (entry.duration(), rate(entry)) {
+                let currency = self.currency_of(entry).unwrap_or(NO_CURRENCY_LABEL);
+                *totals.entry(currency.to_string()).or_default() += earnings(duration, rate);
+            }
+        }
+
+        totals
+    }
+}
@@ -0,0 +1,232 @@
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::{entries::billable_by_default, errors::Result, Entry, Error};
+
+/// The longest an entry built with [`EntryBuilder::with_duration`] may be
+///
+/// Anything longer is almost certainly a unit mix-up in the source data (e.g. milliseconds read as seconds).
+pub const MAX_ENTRY_DURATION: Duration = Duration::from_secs(366 * 24 * 60 * 60);
+
+/// Builds [`Entry`]s with explicit values, e.g. when importing from other trackers
+///
+/// Unless set, the start time defaults to when [`EntryBuilder::build`] is called, and the entry is
+/// left running. Built entries can be added to a list with [`crate::EntryList::bulk_insert`].
+#[derive(Debug, Default)]
+pub struct EntryBuilder {
+    category: Option<String>,
+    project: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    start_time: Option<SystemTime>,
+    end: Option<End>,
+    estimated: bool,
+    billable: Option<bool>,
+    currency: Option<String>,
+    continued_from: Option<Uuid>,
+    allow_future: bool,
+}
+
+/// How the end of a built entry was specified
+#[derive(Debug)]
+enum End {
+    Time(SystemTime),
+    Duration(Duration),
+}
+
+impl EntryBuilder {
+    /// Creates a builder with nothing set
+    pub fn new() -> Self {
+        EntryBuilder::default()
+    }
+
+    /// Sets the category of the entry
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Sets the project of the entry
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Sets the description of the entry
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the tags of the entry
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Sets when the entry started
+    pub fn start_time(mut self, start_time: SystemTime) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Sets when the entry ended
+    ///
+    /// Replaces any duration set with [`EntryBuilder::with_duration`].
+    pub fn end_time(mut self, end_time: SystemTime) -> Self {
+        self.end = Some(End::Time(end_time));
+        self
+    }
+
+    /// Sets how long the entry lasted, so it ends at `start_time + duration`
+    ///
+    /// This is for sources which record a duration rather than an end time. Replaces any end time
+    /// set with [`EntryBuilder::end_time`].
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.end = Some(End::Duration(duration));
+        self
+    }
+
+    /// Marks whether the entry's times are only approximate
+    pub fn estimated(mut self, estimated: bool) -> Self {
+        self.estimated = estimated;
+        self
+    }
+
+    /// Marks whether the entry is billable, which it is unless set otherwise
+    pub fn billable(mut self, billable: bool) -> Self {
+        self.billable = Some(billable);
+        self
+    }
+
+    /// Sets the ISO 4217 code of the currency the entry is billed in
+    pub fn currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+
+    /// Marks the entry as continuing the same task as an earlier entry
+    pub fn continued_from(mut self, id: Uuid) -> Self {
+        self.continued_from = Some(id);
+        self
+    }
+
+    /// Allows the entry to start in the future, e.g. when scheduling time ahead
+    ///
+    /// Off by default, since a future start time is far more likely to be a typo.
+    pub fn allow_future(mut self, allow_future: bool) -> Self {
+        self.allow_future = allow_future;
+        self
+    }
+
+    /// Builds the entry, giving it a new random id
+    ///
+    /// # Errors
+    /// - Returns an error if the start time is in the future, unless allowed with [`EntryBuilder::allow_future`]
+    /// - Returns an error if the end time is before the start time
+    /// - Returns an error if the duration is longer than [`MAX_ENTRY_DURATION`]
+    pub fn build(self) -> Result<Entry> {
+        let now = SystemTime::now();
+        let start_time = self.start_time.unwrap_or(now);
+        if start_time > now && !self.allow_future {
+            return Err(Error::FutureStartTime);
+        }
+
+        let end_time = match self.end {
+            Some(End::Time(end_time)) => {
+                if end_time < start_time {
+                    return Err(Error::EndBeforeStart);
+                }
+                Some(end_time)
+            }
+            Some(End::Duration(duration)) => {
+                if duration > MAX_ENTRY_DURATION {
+                    return Err(Error::DurationTooLong(duration));
+                }
+                Some(
+                    start_time
+                        .checked_add(duration)
+                        .ok_or(Error::DurationTooLong(duration))?,
+                )
+            }
+            None => None,
+        };
+
+        Ok(Entry {
+            id: Uuid::new_v4().into_bytes(),
+            category: self.category,
+            project: self.project,
+            description: self.description,
+            tags: self.tags,
+            start_time,
+            end_time,
+            estimated: self.estimated,
+            billable: self.billable.unwrap_or(true),
+            currency: self.currency,
+            continued_from: self.continued_from.map(Uuid::into_bytes),
+            pauses: Vec::new(),
+            history: Vec::new(),
+        })
+    }
+}
+
+/// An entry as it appears in an import source, which may record a duration instead of an end time
+///
+/// Convert it into an [`Entry`] with [`TryFrom`], which validates it like [`EntryBuilder::build`].
+/// If both `end_time` and `duration` are present, `end_time` wins.
+#[derive(Debug, Deserialize)]
+pub struct EntryRecord {
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    pub start_time: SystemTime,
+    #[serde(default)]
+    pub end_time: Option<SystemTime>,
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    #[serde(default)]
+    pub estimated: bool,
+    #[serde(default = "billable_by_default")]
+    pub billable: bool,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+impl TryFrom<EntryRecord> for Entry {
+    type Error = Error;
+
+    fn try_from(record: EntryRecord) -> Result<Self> {
+        let mut builder = EntryBuilder::new()
+            .tags(record.tags)
+            .start_time(record.start_time)
+            .estimated(record.estimated)
+            .billable(record.billable);
+
+        if let Some(currency) = record.currency {
+            builder = builder.currency(currency);
+        }
+        if let Some(category) = record.category {
+            builder = builder.category(category);
+        }
+        if let Some(project) = record.project {
+            builder = builder.project(project);
+        }
+        if let Some(description) = record.description {
+            builder = builder.description(description);
+        }
+        match (record.end_time, record.duration) {
+            (Some(end_time), _) => builder = builder.end_time(end_time),
+            (None, Some(duration)) => builder = builder.with_duration(duration),
+            (None, None) => {}
+        }
+
+        builder.build()
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::NaiveDate;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, SystemTime},
+};
+
+use uuid::Uuid;
+
+use crate::{aggregate::day_range, errors::Result, Entry, EntryList, Error};
+
+/// Reason an entry was rejected by [`EntryList::bulk_insert`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The entry has no `end_time`; only completed entries can be bulk inserted
+    Running,
+    /// The entry's `end_time` is before its `start_time`
+    EndBeforeStart,
+    /// An entry with the same id is already in the list (or earlier in the same batch)
+    DuplicateId,
+    /// The entry overlaps the existing entry with the given id
+    Overlaps(Uuid),
+}
+
+/// Outcome of a call to [`EntryList::bulk_insert`]
+#[derive(Debug, Default)]
+pub struct BulkResult {
+    /// Ids of the entries that were inserted, in the order they were provided
+    pub accepted: Vec<Uuid>,
+
+    /// Entries that were not inserted, along with why
+    pub rejected: Vec<(Entry, RejectReason)>,
+}
+
+impl EntryList {
+    /// Inserts many completed entries at once, validating each one first
+    ///
+    /// Every entry must have an `end_time` no earlier than its `start_time`, and an id that isn't
+    /// already in use. If [overlaps are rejected](EntryList::set_reject_overlaps), entries
+    /// overlapping any existing entry (including ones accepted earlier in the same batch) are
+    /// rejected too; the running entry is treated as ending now for this purpose.
+    ///
+    /// Valid entries are inserted, invalid ones are handed back in [`BulkResult::rejected`]. This never
+    /// touches the current entry.
+    pub fn bulk_insert(&mut self, entries: Vec<Entry>) -> BulkResult {
+        let mut result = BulkResult::default();
+
+        for entry in entries {
+            match self.validate_bulk_entry(&entry) {
+                Some(reason) => result.rejected.push((entry, reason)),
+                None => {
+                    result.accepted.push(entry.uuid());
+                    self.entries.insert(entry.id(), entry);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Replaces every entry in this list, and the current entry, in one go
+    ///
+    /// The replacement is validated first: ids must be unique, no entry may end before it starts, and
+    /// the only entry allowed to still be running is `current`, which must be one of `entries`. If
+    /// any check fails, the list is left exactly as it was.
+    ///
+    /// # Errors
+    /// - Returns an error if two entries share an id
+    /// - Returns an error if an entry ends before it starts
+    /// - Returns an error if an entry other than `current` is still running
+    /// - Returns an error if `current` isn't among `entries`, or has already finished
+    pub fn replace_all(&mut self, entries: Vec<Entry>, current: Option<Uuid>) -> Result<()> {
+        let mut replacement = HashMap::with_capacity(entries.len());
+
+        for entry in entries {
+            match entry.end_time() {
+                Some(end_time) if end_time < entry.start_time() => {
+                    return Err(Error::EndBeforeStart)
+                }
+                Some(_) => {}
+                None if Some(entry.uuid()) == current => {}
+                None => return Err(Error::UnfinishedEntry(entry.uuid())),
+            }
+
+            if let Some(duplicate) = replacement.insert(entry.id(), entry) {
+                return Err(Error::DuplicateId(duplicate.uuid()));
+            }
+        }
+
+        if let Some(id) = current {
+            match replacement.get(id.as_bytes()) {
+                Some(entry) if entry.end_time().is_none() => {}
+                _ => return Err(Error::InvalidCurrentEntry(id)),
+            }
+        }
+
+        self.entries = replacement;
+        self.current_entry = current.map(Uuid::into_bytes);
+        Ok(())
+    }
+
+    /// Replaces the finished entries which started on the given local day with a single summary entry,
+    /// or one per project if `per_project` is set
+    ///
+    /// Each summary entry starts when the earliest of its entries started and lasts for their summed
+    /// durations, so gaps between them aren't counted. It keeps whatever project, category and
+    /// currency its entries all agree on, along with the union of their tags, but no description.
+    /// It's marked estimated if any of its entries were, and billable only if all of them were.
+    /// Running entries and other days are left alone.
+    ///
+    /// Returns the entries that were removed.
+    pub fn collapse_day(&mut self, date: NaiveDate, per_project: bool) -> Vec<Entry> {
+        let (from, to) = day_range(date);
+        let ids: Vec<_> = self
+            .get_entries_in_range(from, to, true)
+            .into_iter()
+            .filter(|entry| entry.end_time().is_some())
+            .map(Entry::id)
+            .collect();
+        let removed: Vec<Entry> = ids
+            .iter()
+            .map(|id| self.entries.remove(id).expect("Entry was just found"))
+            .collect();
+
+        let mut groups: BTreeMap<Option<&String>, Vec<&Entry>> = BTreeMap::new();
+        for entry in &removed {
+            let key = if per_project { entry.project().as_ref() } else { None };
+            groups.entry(key).or_default().push(entry);
+        }
+
+        for group in groups.into_values() {
+            let summary = summarize(&group);
+            self.entries.insert(summary.id(), summary);
+        }
+
+        removed
+    }
+
+    /// Checks a single entry for [`EntryList::bulk_insert`], returning why it should be rejected (if it should be)
+    fn validate_bulk_entry(&self, entry: &Entry) -> Option<RejectReason> {
+        let end_time = match entry.end_time() {
+            Some(end_time) => end_time,
+            None => return Some(RejectReason::Running),
+        };
+
+        if end_time < entry.start_time() {
+            return Some(RejectReason::EndBeforeStart);
+        }
+
+        if self.entries.contains_key(&entry.id()) {
+            return Some(RejectReason::DuplicateId);
+        }
+
+        if self.reject_overlaps {
+            let now = SystemTime::now();
+            let overlapping = self.entries.values().find(|existing| {
+                let existing_end = existing.end_time().unwrap_or(now);
+                existing.start_time() < end_time && entry.start_time() < existing_end
+            });
+
+            if let Some(existing) = overlapping {
+                return Some(RejectReason::Overlaps(existing.uuid()));
+            }
+        }
+
+        None
+    }
+}
+
+/// Builds the summary entry for a group of finished entries, for [`EntryList::collapse_day`]
+fn summarize(group: &[&Entry]) -> Entry {
+    /// Gets the value every entry in the group has in common, if they do
+    fn shared<T: PartialEq + Clone>(values: impl IntoIterator<Item = Option<T>>) -> Option<T> {
+        let mut values = values.into_iter();
+        let first = values.next()??;
+        values.all(|value| value.as_ref() == Some(&first)).then_some(first)
+    }
+
+    let start_time = group
+        .iter()
+        .map(|entry| entry.start_time())
+        .min()
+        .expect("Groups are never empty");
+    let duration: Duration = group
+        .iter()
+        .filter_map(|entry| entry.duration())
+        .sum();
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in group.iter().flat_map(|entry| entry.tags()) {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+
+    let mut summary = Entry::new(
+        shared(group.iter().map(|entry| entry.project().clone())),
+        None,
+        tags,
+        shared(group.iter().map(|entry| entry.category().clone())),
+    );
+    summary.start_time = start_time;
+    summary.end_time = Some(start_time + duration);
+    summary.estimated = group.iter().any(|entry| entry.estimated());
+    summary.billable = group.iter().all(|entry| entry.billable());
+    summary.currency = shared(group.iter().map(|entry| entry.currency().clone()));
+    summary
+}
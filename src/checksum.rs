@@ -0,0 +1,143 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::ErrorKind,
+    path::Path,
+};
+use uuid::Bytes;
+
+use crate::{
+    entries::{serialize_in_order, sidecar_path},
+    errors::Result,
+    version::supported_version,
+    Entry, EntryList, Error, FORMAT_VERSION,
+};
+
+/// Suffix of the file checksums were kept in before they moved into the data file itself
+const LEGACY_CHECKSUM_SUFFIX: &str = ".sha256";
+
+/// Outcome of checking a data file against its recorded checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrity {
+    /// The file matches its recorded checksum
+    Verified,
+    /// No checksum has been recorded for the file yet, e.g. it was last saved by an older version
+    Unchecked,
+}
+
+/// What's written to a data file: the saved fields of an [`EntryList`], plus a checksum of them
+///
+/// This has to be kept in step with the fields of [`EntryList`] which aren't skipped.
+#[derive(Serialize)]
+#[serde(rename = "EntryList")]
+pub(crate) struct SavedList<'a> {
+    version: u32,
+    entries: InOrder<'a>,
+    current_entry: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_removed: Option<&'a Entry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+}
+
+/// Entries serialized in the order they started, as in [`EntryList`]
+struct InOrder<'a>(&'a HashMap<Bytes, Entry>);
+
+impl Serialize for InOrder<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_in_order(self.0, serializer)
+    }
+}
+
+impl EntryList {
+    /// Checks the data file at the path provided against the checksum recorded in it when it was
+    /// saved
+    ///
+    /// The file is only read, so this also works on files which fail to load because of their
+    /// checksum.
+    ///
+    /// # Errors
+    /// - Returns an error if the file doesn't match its checksum
+    /// - Returns an error if the file is from a newer format version than this build supports
+    /// - Returns an error if anything goes wrong reading the file
+    pub fn verify_checksum<P>(path: P) -> Result<Integrity>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let list = EntryList::parse_ron_file(path, File::open(path)?)?;
+        supported_version(list.version)?;
+        list.check_integrity()
+    }
+
+    /// Deserializes an entry list like [`EntryList::load`], but without checking its checksum
+    ///
+    /// This is for accepting a file that was edited by hand: saving the list returned records a new
+    /// checksum, so the file loads normally again.
+    ///
+    /// # Errors
+    /// - Returns an error if the file is from a newer format version than this build supports
+    /// - Returns an error if anything goes wrong reading the file
+    pub fn load_unverified<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(&path)?;
+        EntryList::from_ron_file(path.as_ref(), file, false)
+    }
+
+    /// Checks a freshly deserialized list against the checksum it was saved with, if any
+    ///
+    /// # Errors
+    /// - Returns an error if the list doesn't match its checksum
+    pub(crate) fn check_integrity(&self) -> Result<Integrity> {
+        match &self.checksum {
+            // Migrating changes what's saved, so a checksum only holds for the version it was saved in
+            Some(_) if self.version != FORMAT_VERSION => Ok(Integrity::Unchecked),
+            Some(expected) if *expected == digest(&self.saved(None))? => Ok(Integrity::Verified),
+            Some(_) => Err(Error::IntegrityCheckFailed),
+            None => Ok(Integrity::Unchecked),
+        }
+    }
+
+    /// Gets what [`EntryList::save`] writes for this list, including its checksum
+    ///
+    /// # Errors
+    /// - Returns an error if the entries can't be serialized
+    pub(crate) fn signed(&self) -> Result<SavedList<'_>> {
+        let checksum = digest(&self.saved(None))?;
+        Ok(self.saved(Some(checksum)))
+    }
+
+    fn saved(&self, checksum: Option<String>) -> SavedList<'_> {
+        SavedList {
+            version: self.version,
+            entries: InOrder(&self.entries),
+            current_entry: self.current_entry,
+            last_removed: self.last_removed.as_ref(),
+            checksum,
+        }
+    }
+}
+
+/// Computes the hex encoded SHA-256 digest of a list's compact serialization
+///
+/// The same entries always serialize the same way, so whether the file was pretty printed doesn't
+/// matter.
+fn digest(list: &SavedList) -> Result<String> {
+    let hash = Sha256::digest(ron::ser::to_string(list)?.as_bytes());
+    Ok(hash.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Removes the checksum file older versions kept next to a data file, which would otherwise go stale
+pub(crate) fn remove_legacy_sidecar(path: &Path) -> Result<()> {
+    match fs::remove_file(sidecar_path(path, LEGACY_CHECKSUM_SUFFIX)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
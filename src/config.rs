@@ -0,0 +1,167 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{errors::Result, Entry, EntryList, Error};
+
+/// User configuration, read from a RON file
+///
+/// Every field is optional, so an empty or partial file is valid.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// The hours during which time may be tracked, if restricted
+    pub work_hours: Option<WorkHours>,
+
+    /// If set, stopped entries have their stored end time rounded up so their duration is a
+    /// multiple of this increment. This alters the saved data, not just how it's displayed.
+    ///
+    /// In the config file this looks like `round_on_stop: Some((secs: 900, nanos: 0))`.
+    pub round_on_stop: Option<Duration>,
+
+    /// If set, `status` warns when the running entry has been going for longer than this
+    ///
+    /// In the config file this looks like `long_running_warning: Some((secs: 36000, nanos: 0))`.
+    pub long_running_warning: Option<Duration>,
+
+    /// Label that entries without a project are grouped under, instead of [`crate::NO_PROJECT_LABEL`]
+    pub no_project_label: Option<String>,
+
+    /// ISO 4217 code of the currency entries are billed in, unless they set their own
+    pub default_currency: Option<String>,
+
+    /// Hourly rate used to work out earnings in reports, for projects without their own rate
+    pub hourly_rate: Option<f64>,
+
+    /// Hourly rates for particular projects, e.g. `{"Client/A": 85.0}`, overriding `hourly_rate`.
+    /// When a report is grouped another way, these are looked up by the group's name instead.
+    pub project_rates: BTreeMap<String, f64>,
+
+    /// Tags counted as other tags when grouping by tag, e.g. `{"mtg": "meeting"}`. Stored tags are left as they are.
+    pub tag_aliases: BTreeMap<String, String>,
+
+    /// Time zone that times are shown in, e.g. `"Europe/Berlin"`, instead of the local one. Entries
+    /// are stored in UTC either way.
+    pub timezone: Option<String>,
+
+    /// strftime-style format that times are shown in, instead of [`crate::DEFAULT_TIME_FORMAT`]
+    pub time_format: Option<String>,
+
+    /// Save the data file as indented RON with one field per line, so it diffs cleanly in git
+    pub pretty_data_file: bool,
+
+    /// Named data files, e.g. `{"work": "/path/work.ron", "personal": "/path/personal.ron"}`
+    pub profiles: BTreeMap<String, PathBuf>,
+
+    /// Project that `start` uses when none is given
+    pub default_project: Option<String>,
+
+    /// Tags that `start` uses when none are given, e.g. `["deep-work"]`
+    pub default_tags: Vec<String>,
+}
+
+impl Config {
+    /// Gets the hourly rate for a project (or category), falling back to `default_rate`
+    pub fn rate_for(&self, name: &str, default_rate: Option<f64>) -> Option<f64> {
+        self.project_rates.get(name).copied().or(default_rate)
+    }
+
+    /// Deserializes a config from the file path provided
+    ///
+    /// # Errors
+    /// - Returns an error if the file can't be read, or isn't a valid config
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(path)?;
+        ron::de::from_bytes(&bytes).map_err(Error::from)
+    }
+
+    /// Deserializes a config from the file path provided, or returns the default config if the
+    /// file does not exist.
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong reading the file (other than not finding it)
+    pub fn load_or_default<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        match Config::load(path) {
+            Ok(config) => Ok(config),
+            Err(Error::File(e)) => {
+                if e.kind() == ErrorKind::NotFound {
+                    Ok(Config::default())
+                } else {
+                    Err(e.into())
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets the data file path registered for the named profile
+    ///
+    /// # Errors
+    /// - Returns an error if no profile with that name is configured
+    pub fn profile_path(&self, name: &str) -> Result<&Path> {
+        self.profiles
+            .get(name)
+            .map(PathBuf::as_path)
+            .ok_or_else(|| Error::UnknownProfile(name.to_string()))
+    }
+}
+
+/// A weekly working schedule, e.g. Monday to Friday 08:00 to 20:00
+///
+/// Times are in the local timezone. In the config file this looks like
+/// `(days: ["Mon", "Tue", "Wed", "Thu", "Fri"], start: "08:00:00", end: "20:00:00")`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkHours {
+    /// The days of the week on which work is allowed
+    pub days: Vec<Weekday>,
+
+    /// The earliest time of day work may start
+    pub start: NaiveTime,
+
+    /// The latest time of day work may end
+    pub end: NaiveTime,
+}
+
+impl WorkHours {
+    /// Checks whether the given instant falls within these working hours
+    pub fn contains(&self, time: SystemTime) -> bool {
+        let local = DateTime::<Local>::from(time);
+        let time_of_day = local.time();
+
+        self.days.contains(&local.weekday()) && self.start <= time_of_day && time_of_day <= self.end
+    }
+}
+
+impl EntryList {
+    /// Checks whether an entry falls (even partly) outside the given working hours
+    ///
+    /// An entry is in violation if it starts or ends outside the working hours, or spans more than
+    /// one day. Running entries are only checked by their start time.
+    pub fn violates_work_hours(&self, entry: &Entry, rules: &WorkHours) -> bool {
+        if !rules.contains(entry.start_time()) {
+            return true;
+        }
+
+        match entry.end_time() {
+            Some(end_time) => {
+                let start_day = DateTime::<Local>::from(entry.start_time()).date_naive();
+                let end_day = DateTime::<Local>::from(end_time).date_naive();
+
+                start_day != end_day || !rules.contains(end_time)
+            }
+            None => false,
+        }
+    }
+}
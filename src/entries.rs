@@ -1,38 +1,82 @@
-use serde::{Deserialize, Serialize};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
     fmt::Display,
     fs::{self, File},
-    io::ErrorKind,
-    path::Path,
-    time::SystemTime,
+    io::{self, BufReader, ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
 };
 use uuid::{Bytes, Uuid};
 
-use crate::{errors::Result, Error};
+use crate::{
+    checksum,
+    errors::Result,
+    version::{check_version, supported_version},
+    EditRecord, Error, FieldChange, FORMAT_VERSION,
+};
 
 /// A single time entry
-#[derive(Debug, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Entry {
-    id: Bytes,
+    pub(crate) id: Bytes,
+
+    #[serde(default)]
+    pub(crate) category: Option<String>,
+    pub(crate) project: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) tags: Vec<String>,
+
+    pub(crate) start_time: SystemTime,
+    pub(crate) end_time: Option<SystemTime>,
+
+    #[serde(default)]
+    pub(crate) estimated: bool,
+
+    /// Whether this entry's time can be billed to a client
+    #[serde(default = "billable_by_default")]
+    pub(crate) billable: bool,
+
+    /// ISO 4217 code of the currency this entry is billed in, if different from the default
+    #[serde(default)]
+    pub(crate) currency: Option<String>,
+
+    /// Id of the earlier entry this one picks up the same task from, if any
+    #[serde(default)]
+    pub(crate) continued_from: Option<Bytes>,
 
-    project: Option<String>,
-    description: Option<String>,
-    tags: Vec<String>,
+    /// Times this entry was paused, each with when it was resumed (if it has been)
+    #[serde(default)]
+    pub(crate) pauses: Vec<(SystemTime, Option<SystemTime>)>,
 
-    start_time: SystemTime,
-    end_time: Option<SystemTime>,
+    /// Changes made to this entry since it was created, oldest first
+    #[serde(default)]
+    pub(crate) history: Vec<EditRecord>,
 }
 
 impl Entry {
-    fn new(project: Option<String>, description: Option<String>, tags: Vec<String>) -> Self {
+    pub(crate) fn new(
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Self {
         Entry {
             id: Uuid::new_v4().into_bytes(),
+            category,
             project,
             description,
             tags,
             start_time: SystemTime::now(),
             end_time: None,
+            estimated: false,
+            billable: true,
+            currency: None,
+            continued_from: None,
+            pauses: Vec::new(),
+            history: Vec::new(),
         }
     }
 
@@ -48,6 +92,22 @@ impl Entry {
         Uuid::from_bytes(self.id)
     }
 
+    /// Get the first [`SHORT_ID_LENGTH`] hex digits of this entry's id, for referring to it on the command line
+    ///
+    /// See [`EntryList::find_by_prefix`].
+    pub fn short_id(&self) -> String {
+        let mut id = self.uuid().simple().to_string();
+        id.truncate(SHORT_ID_LENGTH);
+        id
+    }
+
+    /// Get the category of this entry, if set
+    ///
+    /// Categories sit above projects, e.g. a client with several projects.
+    pub fn category(&self) -> &Option<String> {
+        &self.category
+    }
+
     /// Get the project string of this entry, if set
     pub fn project(&self) -> &Option<String> {
         &self.project
@@ -75,15 +135,147 @@ impl Entry {
         self.end_time
     }
 
-    fn stop(&mut self) {
-        if self.end_time.is_none() {
-            self.end_time = Some(SystemTime::now());
-        } // TODO: 2022-10-15 emit a warning if end_time is Some
+    /// Get the length of this entry, if it's finished, not counting time spent paused
+    ///
+    /// An entry ending before it starts (e.g. after clock skew) has a length of zero.
+    pub fn duration(&self) -> Option<Duration> {
+        self.end_time.map(|end_time| self.length_until(end_time))
+    }
+
+    /// Get the length of this entry so far, measuring a running entry up to now, not counting time
+    /// spent paused
+    ///
+    /// An entry ending before it starts (e.g. after clock skew) has a length of zero.
+    pub fn elapsed(&self) -> Duration {
+        self.length_until(self.end_time.unwrap_or_else(SystemTime::now))
+    }
+
+    /// Check whether this entry is running but paused
+    pub fn is_paused(&self) -> bool {
+        self.end_time.is_none() && matches!(self.pauses.last(), Some((_, None)))
+    }
+
+    /// Get the times this entry was paused, each with when it was resumed (if it has been)
+    pub fn pauses(&self) -> &[(SystemTime, Option<SystemTime>)] {
+        &self.pauses
+    }
+
+    /// Check whether this entry's times are only approximate, e.g. because it was logged from memory
+    pub fn estimated(&self) -> bool {
+        self.estimated
+    }
+
+    /// Check whether this entry's time can be billed to a client, which it can unless marked otherwise
+    pub fn billable(&self) -> bool {
+        self.billable
+    }
+
+    /// Get the currency code this entry is billed in, if set
+    ///
+    /// See [`EntryList::currency_of`] for the currency taking the configured default into account.
+    pub fn currency(&self) -> &Option<String> {
+        &self.currency
+    }
+
+    /// Get the id of the earlier entry this one continues, if any
+    ///
+    /// See [`EntryList::chain_for`] for the whole sequence of linked entries.
+    pub fn continued_from(&self) -> Option<Uuid> {
+        self.continued_from.map(Uuid::from_bytes)
+    }
+
+    /// Measures the time this entry has run since `since`, less any time paused, up to its end (or
+    /// now, if it's still running)
+    pub(crate) fn elapsed_since(&self, since: SystemTime) -> Duration {
+        self.elapsed().saturating_sub(self.length_until(since))
+    }
+
+    /// Measures from `start_time` to `until`, less any time paused in between
+    fn length_until(&self, until: SystemTime) -> Duration {
+        let paused: Duration = self
+            .pauses
+            .iter()
+            .map(|&(paused, resumed)| {
+                let paused = paused.max(self.start_time);
+                let resumed = resumed.unwrap_or(until).min(until);
+                resumed.duration_since(paused).unwrap_or_default()
+            })
+            .sum();
+
+        until
+            .duration_since(self.start_time)
+            .unwrap_or_default()
+            .saturating_sub(paused)
+    }
+
+    /// Sets `end_time`, unless this entry was already stopped, in which case it's left as it was
+    ///
+    /// A paused entry counts as resumed when it stops, so the pause doesn't stay open.
+    fn stop(&mut self, end_time: SystemTime) -> StopOutcome {
+        if self.end_time.is_some() {
+            return StopOutcome::AlreadyStopped;
+        }
+
+        let end_time = end_time.max(self.start_time);
+        if let Some((_, resumed @ None)) = self.pauses.last_mut() {
+            *resumed = Some(end_time);
+        }
+        self.end_time = Some(end_time);
+        self.record(FieldChange::EndTime(None, self.end_time));
+        StopOutcome::Stopped
+    }
+
+    /// Moves `end_time` forward so this entry's duration is a whole multiple of `increment`
+    fn round_end_time(&mut self, increment: Duration) {
+        let step = increment.as_nanos();
+        if step == 0 {
+            return;
+        }
+
+        if let (Some(end_time), Some(duration)) = (self.end_time, self.duration()) {
+            let elapsed = duration.as_nanos();
+            let rounded = elapsed.div_ceil(step) * step;
+            if rounded == elapsed {
+                return;
+            }
+
+            self.end_time = Some(end_time + Duration::from_nanos((rounded - elapsed) as u64));
+            self.record(FieldChange::EndTime(Some(end_time), self.end_time));
+        }
     }
 }
 
+/// Whether stopping an entry actually set its end time
+#[must_use]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// The entry was running, and now has an end time
+    Stopped,
+    /// The entry already had an end time, which was left unchanged
+    AlreadyStopped,
+}
+
+/// Entries are billable unless marked otherwise, including those saved before billability was tracked
+pub(crate) fn billable_by_default() -> bool {
+    true
+}
+
+/// The result of [`EntryList::switch_entry`]
+///
+/// This doesn't borrow the list, so it can be kept while making further changes.
+#[derive(Debug)]
+pub struct SwitchOutcome {
+    /// The entry that was running beforehand, as it was once stopped
+    pub stopped: Option<Entry>,
+    /// Id of the newly started entry
+    pub started: Uuid,
+}
+
 impl Display for Entry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(category) = &self.category {
+            write!(f, "[{}] ", category)?;
+        }
         write!(
             f,
             "{}: {} {:?}",
@@ -102,7 +294,7 @@ impl PartialEq for Entry {
 
 impl PartialOrd for Entry {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.start_time.partial_cmp(&other.start_time)
+        Some(self.cmp(other))
     }
 }
 
@@ -112,59 +304,225 @@ impl Ord for Entry {
     }
 }
 
-/// Error message to use for `.expect(...)` when attempting to retrieve the current entry from the entry list
+/// Label used in place of a project name for entries without one, unless configured otherwise
 ///
-/// This case comes up a lot, so it's useful to standardize the message.
-const NO_CURRENT_ENTRY_MESSAGE: &str = "Failure retrieving current entry from entry list";
+/// See [`EntryList::set_no_project_label`].
+pub const NO_PROJECT_LABEL: &str = "(no project)";
+
+/// Label used in place of a category name for entries without one
+pub const NO_CATEGORY_LABEL: &str = "(no category)";
+
+/// Number of hex digits shown by [`Entry::short_id`], which is plenty to tell entries apart
+pub const SHORT_ID_LENGTH: usize = 8;
 
 /// A set of time entries
 ///
 /// We have to use raw [`Bytes`] here because [`Uuid`] doesn't implement [`Serialize`] or [`Deserialize`].
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct EntryList {
+    /// Format version of the file this list was read from, once migrated (see [`FORMAT_VERSION`])
+    #[serde(default)]
+    pub(crate) version: u32,
+
     /// All entries
+    ///
+    /// These are written oldest first, so saving the same entries always gives the same file.
+    #[serde(serialize_with = "serialize_in_order")]
     pub(crate) entries: HashMap<Bytes, Entry>,
 
     /// The currently running entry, if any.
     ///
     /// This field is set when a new entry is started, and cleared when it is stopped (or reset)
     pub(crate) current_entry: Option<Bytes>,
+
+    /// The entry most recently cleared or deleted, kept so it can be restored with
+    /// [`EntryList::undo_remove`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_removed: Option<Entry>,
+
+    /// Checksum the list was saved with, checked when it's loaded (see [`EntryList::verify_checksum`])
+    ///
+    /// This is written by [`EntryList::save`] rather than serialized with the rest of the list.
+    #[serde(default, skip_serializing)]
+    pub(crate) checksum: Option<String>,
+
+    /// If set, entries are rounded when stopped (see [`EntryList::set_round_on_stop`])
+    #[serde(skip)]
+    round_on_stop: Option<Duration>,
+
+    /// Overrides [`NO_PROJECT_LABEL`] (see [`EntryList::set_no_project_label`])
+    #[serde(skip)]
+    no_project_label: Option<String>,
+
+    /// Set for lists opened with [`EntryList::load_readonly`], which refuse to be saved
+    #[serde(skip)]
+    pub(crate) read_only: bool,
+
+    /// Currency for entries without their own (see [`EntryList::set_default_currency`])
+    #[serde(skip)]
+    default_currency: Option<String>,
+
+    /// Tags folded into others when grouping (see [`EntryList::set_tag_aliases`])
+    #[serde(skip)]
+    tag_aliases: BTreeMap<String, String>,
+
+    /// Whether entries may be started in the future (see [`EntryList::set_allow_future_starts`])
+    #[serde(skip)]
+    allow_future_starts: bool,
+
+    /// Whether totals leave out entries which aren't billable (see [`EntryList::set_billable_only`])
+    #[serde(skip)]
+    billable_only: bool,
+
+    /// Whether bulk inserts refuse overlapping entries (see [`EntryList::set_reject_overlaps`])
+    #[serde(skip)]
+    pub(crate) reject_overlaps: bool,
+
+    /// A current entry id dropped on load because no entry had it, kept for
+    /// [`EntryList::validate`]
+    #[serde(skip)]
+    pub(crate) missing_current_entry: Option<Bytes>,
 }
 
 impl EntryList {
     /// Creates a new, empty list
     pub fn new() -> Self {
         EntryList {
+            version: FORMAT_VERSION,
             entries: HashMap::new(),
             current_entry: None,
+            last_removed: None,
+            checksum: None,
+            round_on_stop: None,
+            no_project_label: None,
+            read_only: false,
+            default_currency: None,
+            tag_aliases: BTreeMap::new(),
+            allow_future_starts: false,
+            billable_only: false,
+            reject_overlaps: false,
+            missing_current_entry: None,
         }
     }
 
-    /// Deserializes an entry list from the file path provided
+    /// Sets the rounding policy applied whenever an entry is stopped
+    ///
+    /// When set to `Some(increment)`, stopping an entry moves its `end_time` forward so the stored
+    /// duration is a whole multiple of `increment`. Note that this changes the saved data itself,
+    /// unlike rounding for display, and can't be undone afterwards. When `None` (the default), end
+    /// times are stored exactly as recorded.
+    pub fn set_round_on_stop(&mut self, increment: Option<Duration>) {
+        self.round_on_stop = increment;
+    }
+
+    /// Sets the label that entries without a project are grouped under in aggregations and reports
+    ///
+    /// When `None` (the default), [`NO_PROJECT_LABEL`] is used.
+    pub fn set_no_project_label(&mut self, label: Option<String>) {
+        self.no_project_label = label;
+    }
+
+    /// Gets the label that entries without a project are grouped under
+    pub fn no_project_label(&self) -> &str {
+        self.no_project_label.as_deref().unwrap_or(NO_PROJECT_LABEL)
+    }
+
+    /// Gets the project of an entry for grouping, falling back to [`EntryList::no_project_label`]
+    pub fn project_label<'a>(&'a self, entry: &'a Entry) -> &'a str {
+        entry.project().as_deref().unwrap_or(self.no_project_label())
+    }
+
+    /// Gets the category of an entry for grouping, falling back to [`NO_CATEGORY_LABEL`]
+    pub fn category_label<'a>(&self, entry: &'a Entry) -> &'a str {
+        entry.category().as_deref().unwrap_or(NO_CATEGORY_LABEL)
+    }
+
+    /// Sets whether [`EntryList::start_entry_at`] accepts start times in the future, e.g. for scheduling ahead
+    ///
+    /// Off by default, since a future start time is far more likely to be a typo.
+    pub fn set_allow_future_starts(&mut self, allow: bool) {
+        self.allow_future_starts = allow;
+    }
+
+    /// Sets the tags which count as other tags in aggregations and reports, e.g. `mtg -> meeting`
+    ///
+    /// Stored tags are never changed; aliases only apply when grouping by tag.
+    pub fn set_tag_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.tag_aliases = aliases;
+    }
+
+    /// Gets the tag that the given tag counts as, following [`EntryList::set_tag_aliases`]
+    pub fn tag_label<'a>(&'a self, tag: &'a str) -> &'a str {
+        resolve_alias(&self.tag_aliases, tag)
+    }
+
+    /// Sets the currency that entries without their own currency are billed in
+    pub fn set_default_currency(&mut self, currency: Option<String>) {
+        self.default_currency = currency;
+    }
+
+    /// Sets whether totals and reports only count [billable](Entry::billable) entries
+    ///
+    /// This applies to the totals by project, category, tag or week (rounded or not), to
+    /// [`EntryList::project_totals`], [`EntryList::tag_totals`] and [`EntryList::weekly_totals`],
+    /// and to [`EntryList::report_markdown`].
+    pub fn set_billable_only(&mut self, billable_only: bool) {
+        self.billable_only = billable_only;
+    }
+
+    /// Sets whether [`EntryList::bulk_insert`] rejects entries that overlap existing ones
+    ///
+    /// Off by default, so imports keep whatever was tracked, overlapping or not.
+    pub fn set_reject_overlaps(&mut self, reject: bool) {
+        self.reject_overlaps = reject;
+    }
+
+    /// Checks whether an entry should be counted, following [`EntryList::set_billable_only`]
+    pub(crate) fn counts_for_billing(&self, entry: &Entry) -> bool {
+        entry.billable || !self.billable_only
+    }
+
+    /// Gets the currency an entry is billed in, falling back to the default currency
+    pub fn currency_of<'a>(&'a self, entry: &'a Entry) -> Option<&'a str> {
+        entry
+            .currency()
+            .as_deref()
+            .or(self.default_currency.as_deref())
+    }
+
+    /// Deserializes an entry list from the file path provided, upgrading it if it's from an older
+    /// format version
+    ///
+    /// If a checksum was recorded when the file was last saved, the file is checked against it. A
+    /// file edited by hand can be accepted with [`EntryList::load_unverified`].
     ///
     /// # Errors
+    /// - Returns an error if the file doesn't match its checksum
+    /// - Returns an error if the file is from a newer format version than this build supports
     /// - Returns an error if anything goes wrong reading the file
     pub fn load<P>(path: P) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        match fs::read(path) {
-            Ok(bytes) => ron::de::from_bytes(&bytes).map_err(Error::from),
-            Err(e) => Err(e.into()),
-        }
+        let file = File::open(&path)?;
+        EntryList::from_ron_file(path.as_ref(), file, true)
     }
 
     /// Deserializes an entry list from the file path provided, or creates a new
     /// one if the file does not exist.
     ///
+    /// If a checksum was recorded when the file was last saved, the file is checked against it.
+    ///
     /// # Errors
+    /// - Returns an error if the file doesn't match its checksum
+    /// - Returns an error if the file is from a newer format version than this build supports
     /// - Returns and error if anything goes wrong reading the file (other than not finding it)
     pub fn load_or_create<P>(path: P) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        match fs::read(path) {
-            Ok(bytes) => ron::de::from_bytes(&bytes).map_err(Error::from),
+        match File::open(&path) {
+            Ok(file) => EntryList::from_ron_file(path.as_ref(), file, true),
             Err(e) => {
                 if e.kind() == ErrorKind::NotFound {
                     Ok(EntryList::new())
@@ -175,85 +533,865 @@ impl EntryList {
         }
     }
 
+    /// Deserializes an entry list from an open RON data file, after checking it against its
+    /// checksum (if `verify` is set), upgrading it from older format versions, then repairs any
+    /// inconsistencies which would otherwise cause panics later on
+    pub(crate) fn from_ron_file(path: &Path, file: File, verify: bool) -> Result<Self> {
+        let list = EntryList::parse_ron_file(path, file)?;
+        EntryList::prepare_loaded(list, verify)
+    }
+
+    /// Deserializes an open RON data file as it is, without checking or migrating it
+    pub(crate) fn parse_ron_file(path: &Path, file: File) -> Result<Self> {
+        match ron::de::from_reader(BufReader::new(file)) {
+            Ok(list) => Ok(list),
+            Err(e) => {
+                // A file from a newer version may not parse at all, so give a clearer error for it
+                check_version(BufReader::new(File::open(path)?))?;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Deserializes an entry list from a RON string, as written by [`EntryList::to_ron_string`]
+    ///
+    /// Like [`EntryList::load`], the list is checked against its checksum, and lists from older
+    /// format versions are migrated.
+    ///
+    /// # Errors
+    /// - Returns an error if the string doesn't match its checksum
+    /// - Returns an error if the string isn't a valid entry list
+    /// - Returns an error if it's from a newer format version than this build supports
+    pub fn from_ron_str(s: &str) -> Result<Self> {
+        match ron::from_str(s) {
+            Ok(list) => EntryList::prepare_loaded(list, true),
+            Err(e) => {
+                check_version(s.as_bytes())?;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Serializes this entry list to a RON string, in the same form [`EntryList::save`] writes,
+    /// checksum included
+    ///
+    /// # Errors
+    /// - Returns an error if the entries can't be serialized
+    pub fn to_ron_string(&self) -> Result<String> {
+        Ok(ron::ser::to_string(&self.signed()?)?)
+    }
+
+    /// Checks, migrates and tidies up a freshly deserialized list, for [`EntryList::load`] and
+    /// [`EntryList::from_ron_str`]
+    fn prepare_loaded(mut list: EntryList, verify: bool) -> Result<Self> {
+        let version = supported_version(list.version)?;
+        if verify {
+            list.check_integrity()?;
+        }
+        list.migrate(version);
+
+        // Make sure every entry is stored under its own id, in case the file was edited by hand
+        list.entries = list
+            .entries
+            .into_values()
+            .map(|entry| (entry.id, entry))
+            .collect();
+
+        // A current entry which doesn't exist can be left behind if we crashed mid-save
+        if let Some(id) = list.current_entry {
+            if !list.entries.contains_key(&id) {
+                list.current_entry = None;
+                list.missing_current_entry = Some(id);
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Deserializes an entry list for querying only, from the file path provided
+    ///
+    /// Like [`EntryList::load_or_create`], a missing file gives an empty list. The file is never
+    /// locked or written to, so this is safe to use while another process is modifying it; the list
+    /// returned refuses to be saved.
+    ///
+    /// # Errors
+    /// - Returns and error if anything goes wrong reading the file (other than not finding it)
+    pub fn load_readonly<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut list = EntryList::load_or_create(path)?;
+        list.read_only = true;
+        Ok(list)
+    }
+
     /// Serializes and saves this entry list to the path provided
+    ///
+    /// A checksum of the entries is written in the file, and checked when it's loaded. The file is
+    /// replaced atomically where possible, so a crash mid-save leaves the old contents (and their
+    /// checksum) intact rather than a truncated file.
+    ///
+    /// # Errors
+    /// - Returns an error if this list was opened with [`EntryList::load_readonly`]
+    /// - Returns an error if anything goes wrong writing the file
     pub fn save<P>(&self, path: P) -> Result<()>
     where
         P: AsRef<Path>,
     {
-        let out_file = File::create(path)?;
-        ron::ser::to_writer(out_file, self)?;
-        Ok(())
+        let bytes = self.to_ron_string()?.into_bytes();
+        self.save_bytes(path.as_ref(), &bytes)
+    }
+
+    /// Like [`EntryList::save`], but writes indented RON with one field per line
+    ///
+    /// The file is several times larger, but changes to it diff cleanly, e.g. when it's kept in git.
+    /// Either kind of file can be loaded.
+    ///
+    /// # Errors
+    /// - Returns an error if this list was opened with [`EntryList::load_readonly`]
+    /// - Returns an error if anything goes wrong writing the file
+    pub fn save_pretty<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let config = PrettyConfig::new().compact_arrays(true);
+        let bytes = ron::ser::to_string_pretty(&self.signed()?, config)?.into_bytes();
+        self.save_bytes(path.as_ref(), &bytes)
+    }
+
+    /// Writes out serialized entries, for [`EntryList::save`]
+    fn save_bytes(&self, path: &Path, bytes: &[u8]) -> Result<()> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+
+        write_atomic(path, bytes)?;
+        checksum::remove_legacy_sidecar(path)
     }
 
     /// Starts a new entry
     ///
-    /// Returns the newly created [`Entry`]
+    /// If an entry is already running, it's stopped first when `stop_running` is set, just like
+    /// [`EntryList::switch_entry`]. Otherwise, nothing is started.
+    ///
+    /// # Errors
+    /// - Returns an error if an entry is already running and `stop_running` isn't set
+    /// - Returns an error if the current entry is missing from the list
     pub fn start_entry(
         &mut self,
         project: Option<String>,
         description: Option<String>,
         tags: Vec<String>,
-    ) -> &Entry {
-        let entry = Entry::new(project, description, tags);
-        let id = entry.id;
+        category: Option<String>,
+        stop_running: bool,
+    ) -> Result<SwitchOutcome> {
+        self.check_can_start(stop_running)?;
+        self.switch_entry(project, description, tags, category)
+    }
 
-        self.current_entry = Some(id);
-        self.entries.insert(id, entry);
+    /// Refuses to start an entry while another is running, unless `stop_running` is set
+    fn check_can_start(&self, stop_running: bool) -> Result<()> {
+        if !stop_running {
+            if let Some(entry) = self.current_entry()?.filter(|e| e.end_time.is_none()) {
+                return Err(Error::EntryAlreadyRunning(entry.uuid()));
+            }
+        }
 
-        self.entries.get(&id).expect(NO_CURRENT_ENTRY_MESSAGE)
+        Ok(())
     }
 
     /// Stops the current entry, if any
     ///
-    /// Returns the [`Entry`] of the entry stopped, if anything was actually stopped
+    /// Returns the [`Entry`] of the entry stopped, if there was a current entry, along with whether
+    /// it was actually running. An entry which had already been stopped keeps its end time.
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
     pub fn stop_current_entry(
         &mut self,
         project: Option<String>,
         description: Option<String>,
         tags: Vec<String>,
-    ) -> Option<&Entry> {
+        category: Option<String>,
+    ) -> Result<Option<(&Entry, StopOutcome)>> {
+        self.stop_current_entry_at(SystemTime::now(), project, description, tags, category)
+    }
+
+    /// Stops the current entry, if any, as of the time provided rather than now
+    ///
+    /// This is for trimming time that shouldn't count, e.g. while the machine was asleep. An end time
+    /// before the entry's start is moved up to the start.
+    /// Returns the same as [`EntryList::stop_current_entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
+    pub fn stop_current_entry_at(
+        &mut self,
+        end_time: SystemTime,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Result<Option<(&Entry, StopOutcome)>> {
         if let Some(id) = self.current_entry {
-            let entry = self.entries.get_mut(&id).expect(NO_CURRENT_ENTRY_MESSAGE);
+            let entry = self
+                .entries
+                .get_mut(&id)
+                .ok_or(Error::CorruptState(Uuid::from_bytes(id)))?;
 
             // Stop the timer
-            entry.stop();
+            let outcome = entry.stop(end_time);
+            if outcome == StopOutcome::Stopped {
+                if let Some(increment) = self.round_on_stop {
+                    entry.round_end_time(increment);
+                }
+            }
             self.current_entry = None;
 
             // Update information based on what was provided
-            if project.is_some() {
-                entry.project = project;
-            }
-            if description.is_some() {
-                entry.description = description;
-            }
-            if tags.len() > 0 {
-                entry.tags = tags;
-            }
+            let tags = (!tags.is_empty()).then_some(tags);
+            entry.update_details(project, description, tags, category, None);
 
-            Some(entry)
+            Ok(Some((entry, outcome)))
         } else {
-            None
+            Ok(None)
+        }
+    }
+
+    /// Stops the current entry, if any, and starts a new one in a single step
+    ///
+    /// The stopped entry ends at exactly the same instant the new entry starts, so no time is lost
+    /// between them. The round-on-stop policy isn't applied here, since rounding the stopped entry
+    /// would leave it overlapping the new one.
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
+    pub fn switch_entry(
+        &mut self,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Result<SwitchOutcome> {
+        self.switch_entry_at(SystemTime::now(), project, description, tags, category)
+    }
+
+    /// Starts a new entry picking up where the most recently finished entry left off
+    ///
+    /// The new entry copies that entry's project, description, tags and category, and records it
+    /// in [`Entry::continued_from`]. Like [`EntryList::switch_entry`], any running entry is stopped
+    /// first; the entry continued is the last one to finish before that.
+    /// Returns `None`, changing nothing, if no entry has finished yet
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
+    pub fn resume_last(&mut self) -> Result<Option<SwitchOutcome>> {
+        let Some(last) = self.last_completed_n(1).pop().cloned() else {
+            return Ok(None);
+        };
+
+        let outcome =
+            self.switch_entry(last.project, last.description, last.tags, last.category)?;
+        let started = self
+            .entries
+            .get_mut(outcome.started.as_bytes())
+            .expect("Entry was just started");
+        started.continued_from = Some(last.id);
+        Ok(Some(outcome))
+    }
+
+    /// Starts a finished entry running again, e.g. after stopping it by accident
+    ///
+    /// The entry keeps its `start_time` and becomes the current entry. The time since it was
+    /// stopped counts towards it, as if it had never stopped, so only an entry which nothing has
+    /// started after can be reopened.
+    ///
+    /// # Errors
+    /// - Returns an error if an entry is already running, including this one
+    /// - Returns an error if no entry has the given id
+    /// - Returns an error if another entry started at or after this one
+    /// - Returns an error if the current entry is missing from the list
+    pub fn reopen_entry(&mut self, id: Uuid) -> Result<&Entry> {
+        self.check_can_start(false)?;
+
+        let start_time = self
+            .get_entry(id)
+            .ok_or(Error::EntryNotFound(id))?
+            .start_time;
+        if let Some(later) = self
+            .entries
+            .values()
+            .filter(|other| other.id != *id.as_bytes() && other.start_time >= start_time)
+            .min_by_key(|other| other.start_time)
+        {
+            return Err(Error::ReopenWouldOverlap(later.uuid()));
+        }
+
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .expect("Entry was just found");
+        let end_time = entry.end_time.take();
+        entry.record(FieldChange::EndTime(end_time, None));
+        self.current_entry = Some(entry.id);
+        Ok(entry)
+    }
+
+    /// Starts a new entry from a time in the past, e.g. when the timer was forgotten
+    ///
+    /// Like [`EntryList::start_entry`], a running entry is stopped first when `stop_running` is set.
+    /// It's stopped when the new entry starts so they don't overlap, unless the new entry starts
+    /// before it did, in which case it's stopped now.
+    ///
+    /// # Errors
+    /// - Returns an error if `start` is in the future, unless allowed with [`EntryList::set_allow_future_starts`]
+    /// - Returns an error if an entry is already running and `stop_running` isn't set
+    /// - Returns an error if the current entry is missing from the list
+    pub fn start_entry_at(
+        &mut self,
+        start: SystemTime,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+        stop_running: bool,
+    ) -> Result<SwitchOutcome> {
+        if start > SystemTime::now() && !self.allow_future_starts {
+            return Err(Error::FutureStartTime);
+        }
+        self.check_can_start(stop_running)?;
+
+        self.switch_entry_at(start, project, description, tags, category)
+    }
+
+    /// Adds an entry which has already finished, without touching the current entry
+    ///
+    /// Returns the newly created [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if `end` is before `start`
+    /// - Returns an error if `start` is in the future, unless allowed with [`EntryList::set_allow_future_starts`]
+    pub fn add_entry(
+        &mut self,
+        start: SystemTime,
+        end: SystemTime,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Result<&Entry> {
+        if start > SystemTime::now() && !self.allow_future_starts {
+            return Err(Error::FutureStartTime);
+        }
+        if end < start {
+            return Err(Error::EndBeforeStart);
+        }
+
+        let mut entry = Entry::new(project, description, tags, category);
+        entry.start_time = start;
+        entry.end_time = Some(end);
+        let id = entry.id;
+
+        self.entries.insert(id, entry);
+        Ok(self.entries.get(&id).expect("Entry was just added"))
+    }
+
+    /// Stops the current entry (see [`EntryList::start_entry_at`]) and starts a new one at `start`
+    fn switch_entry_at(
+        &mut self,
+        start: SystemTime,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Result<SwitchOutcome> {
+        let now = SystemTime::now();
+
+        // An entry that was already stopped wasn't stopped by this switch, so isn't reported
+        let mut stopped = None;
+        if let Some(id) = self.current_entry {
+            let entry = self
+                .entries
+                .get_mut(&id)
+                .ok_or(Error::CorruptState(Uuid::from_bytes(id)))?;
+            let end_time = if start > entry.start_time {
+                start.min(now)
+            } else {
+                now
+            };
+            if entry.stop(end_time) == StopOutcome::Stopped {
+                stopped = Some(entry.clone());
+            }
+            self.current_entry = None;
         }
+
+        let mut entry = Entry::new(project, description, tags, category);
+        entry.start_time = start;
+        let id = entry.id;
+
+        self.current_entry = Some(id);
+        self.entries.insert(id, entry);
+
+        Ok(SwitchOutcome {
+            stopped,
+            started: Uuid::from_bytes(id),
+        })
+    }
+
+    /// Pauses the current entry, so the time until it's resumed doesn't count towards its duration
+    ///
+    /// Returns the [`Entry`] paused, if there was a current entry
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is already paused
+    /// - Returns an error if the current entry is missing from the list
+    pub fn pause_current_entry(&mut self) -> Result<Option<&Entry>> {
+        let Some(entry) = self.current_entry_mut()? else {
+            return Ok(None);
+        };
+        if entry.is_paused() {
+            return Err(Error::AlreadyPaused);
+        }
+
+        entry.pauses.push((SystemTime::now(), None));
+        Ok(Some(entry))
+    }
+
+    /// Resumes the current entry after [`EntryList::pause_current_entry`]
+    ///
+    /// Returns the [`Entry`] resumed, if there was a current entry
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry isn't paused
+    /// - Returns an error if the current entry is missing from the list
+    pub fn resume_current_entry(&mut self) -> Result<Option<&Entry>> {
+        let Some(entry) = self.current_entry_mut()? else {
+            return Ok(None);
+        };
+        match entry.pauses.last_mut() {
+            Some((_, resumed @ None)) => *resumed = Some(SystemTime::now()),
+            _ => return Err(Error::NotPaused),
+        }
+
+        Ok(Some(entry))
     }
 
     /// Stops and discards the current entry, if any
     ///
-    /// This effectively lets you "cancel" a entry that was started incorrectly
+    /// This effectively lets you "cancel" a entry that was started incorrectly. The entry can be
+    /// brought back with [`EntryList::undo_remove`].
     /// Returns the [`Entry`] of the entry stopped, if anything was actually stopped
-    pub fn clear_current_entry(&mut self) -> Option<Entry> {
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
+    pub fn clear_current_entry(&mut self) -> Result<Option<Entry>> {
         if let Some(id) = self.current_entry {
-            let entry = self.entries.remove(&id).expect(NO_CURRENT_ENTRY_MESSAGE);
+            let entry = self
+                .entries
+                .remove(&id)
+                .ok_or(Error::CorruptState(Uuid::from_bytes(id)))?;
             self.current_entry = None;
+            self.last_removed = Some(entry.clone());
 
-            Some(entry)
+            Ok(Some(entry))
         } else {
-            None
+            Ok(None)
+        }
+    }
+
+    /// Changes the details of any entry by its id, whether it's finished or running, including
+    /// whether it's billable
+    ///
+    /// Fields given as `Some` are overwritten, and fields given as `None` are left as they are, so
+    /// `Some(Vec::new())` clears the tags while `None` keeps them. Each change is added to the
+    /// entry's [history](Entry::history).
+    /// Returns the updated [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    pub fn edit_entry(
+        &mut self,
+        id: Uuid,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        category: Option<String>,
+        billable: Option<bool>,
+    ) -> Result<&Entry> {
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .ok_or(Error::EntryNotFound(id))?;
+
+        entry.update_details(project, description, tags, category, billable);
+        Ok(entry)
+    }
+
+    /// Adds tags to any entry by its id, keeping the tags it already has
+    ///
+    /// Tags the entry already has (or that are given twice) aren't added again. The change is added
+    /// to the entry's [history](Entry::history).
+    /// Returns the updated [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    pub fn add_tags(&mut self, id: Uuid, tags: Vec<String>) -> Result<&Entry> {
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .ok_or(Error::EntryNotFound(id))?;
+
+        let mut updated = entry.tags.clone();
+        for tag in tags {
+            if !updated.contains(&tag) {
+                updated.push(tag);
+            }
+        }
+        entry.update_details(None, None, Some(updated), None, None);
+        Ok(entry)
+    }
+
+    /// Removes tags from any entry by its id, keeping the rest
+    ///
+    /// Tags the entry doesn't have are ignored. The change is added to the entry's
+    /// [history](Entry::history).
+    /// Returns the updated [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    pub fn remove_tags(&mut self, id: Uuid, tags: Vec<String>) -> Result<&Entry> {
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .ok_or(Error::EntryNotFound(id))?;
+
+        let mut updated = entry.tags.clone();
+        updated.retain(|tag| !tags.contains(tag));
+        entry.update_details(None, None, Some(updated), None, None);
+        Ok(entry)
+    }
+
+    /// Marks whether an entry's times are only approximate, by its id
+    ///
+    /// Returns the updated [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    pub fn set_estimated(&mut self, id: Uuid, estimated: bool) -> Result<&Entry> {
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .ok_or(Error::EntryNotFound(id))?;
+        entry.estimated = estimated;
+        Ok(entry)
+    }
+
+    /// Marks whether an entry is billable, by its id, without recording it in the entry's history
+    ///
+    /// This is meant for setting up new entries; use [`EntryList::edit_entry`] for later changes.
+    /// Returns the updated [`Entry`]
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    pub fn set_billable(&mut self, id: Uuid, billable: bool) -> Result<&Entry> {
+        let entry = self
+            .entries
+            .get_mut(id.as_bytes())
+            .ok_or(Error::EntryNotFound(id))?;
+        entry.billable = billable;
+        Ok(entry)
+    }
+
+    /// Removes any entry by its id, whether it's finished or running
+    ///
+    /// If the entry removed was the current entry, there's no current entry afterwards. The entry
+    /// can be brought back with [`EntryList::undo_remove`].
+    /// Returns the [`Entry`] removed, if there was one with that id
+    pub fn delete_entry(&mut self, id: Uuid) -> Option<Entry> {
+        let entry = self.entries.remove(id.as_bytes())?;
+        if self.current_entry == Some(entry.id) {
+            self.current_entry = None;
+        }
+        self.last_removed = Some(entry.clone());
+
+        Some(entry)
+    }
+
+    /// Removes every entry the predicate returns `true` for, including the running entry if it matches
+    ///
+    /// If the current entry is removed, there's no current entry afterwards. Unlike
+    /// [`EntryList::delete_entry`], this can't be undone with [`EntryList::undo_remove`].
+    /// Returns the entries removed, oldest first
+    pub fn delete_matching(&mut self, predicate: impl Fn(&Entry) -> bool) -> Vec<Entry> {
+        let ids: Vec<_> = self
+            .entries
+            .values()
+            .filter(|entry| predicate(entry))
+            .map(|entry| entry.id)
+            .collect();
+        if self.current_entry.is_some_and(|id| ids.contains(&id)) {
+            self.current_entry = None;
+        }
+
+        let mut removed: Vec<_> = ids
+            .iter()
+            .filter_map(|id| self.entries.remove(id))
+            .collect();
+        removed.sort_unstable_by_key(|entry| (entry.start_time, entry.id));
+        removed
+    }
+
+    /// Puts back the entry most recently removed by [`EntryList::clear_current_entry`] or
+    /// [`EntryList::delete_entry`]
+    ///
+    /// A restored entry that was still running becomes the current entry again, unless another
+    /// entry has been started since. In that case it's stopped when that entry started, so only one
+    /// entry is ever running. Only the last removal can be undone.
+    /// Returns the restored [`Entry`], if there was one to restore
+    pub fn undo_remove(&mut self) -> Option<&Entry> {
+        let mut entry = self.last_removed.take()?;
+        let id = entry.id;
+
+        if entry.end_time.is_none() {
+            match self.current_entry.and_then(|id| self.entries.get(&id)) {
+                Some(current) => {
+                    let _ = entry.stop(current.start_time);
+                }
+                None => self.current_entry = Some(id),
+            }
+        }
+
+        self.entries.insert(id, entry);
+        self.entries.get(&id)
+    }
+
+    /// Replaces a finished entry with two contiguous ones, the first ending and the second starting
+    /// at `at`
+    ///
+    /// Both halves keep the original's details and history and get new ids, and any pause is
+    /// divided between them. Each half records the split in its history.
+    /// Returns the ids of the first and second halves
+    ///
+    /// # Errors
+    /// - Returns an error if there's no entry with that id
+    /// - Returns an error if the entry hasn't finished
+    /// - Returns an error if `at` isn't strictly after the entry's start and before its end
+    pub fn split_entry(&mut self, id: Uuid, at: SystemTime) -> Result<(Uuid, Uuid)> {
+        let original = self.get_entry(id).ok_or(Error::EntryNotFound(id))?;
+        let end = original.end_time.ok_or(Error::EntryNotFinished(id))?;
+        if at <= original.start_time || at >= end {
+            return Err(Error::SplitOutsideEntry);
+        }
+
+        let mut first = original.clone();
+        first.id = Uuid::new_v4().into_bytes();
+        first.end_time = Some(at);
+        first.record(FieldChange::Split(original.id, at));
+        first.pauses = original
+            .pauses
+            .iter()
+            .filter(|(paused, _)| *paused < at)
+            .map(|&(paused, resumed)| (paused, resumed.map(|resumed| resumed.min(at))))
+            .collect();
+
+        let mut second = original.clone();
+        second.id = Uuid::new_v4().into_bytes();
+        second.start_time = at;
+        second.continued_from = None;
+        second.record(FieldChange::Split(original.id, at));
+        second.pauses = original
+            .pauses
+            .iter()
+            .filter(|(_, resumed)| resumed.is_none_or(|resumed| resumed > at))
+            .map(|&(paused, resumed)| (paused.max(at), resumed))
+            .collect();
+
+        let ids = (Uuid::from_bytes(first.id), Uuid::from_bytes(second.id));
+        self.entries.remove(id.as_bytes());
+        self.entries.insert(first.id, first);
+        self.entries.insert(second.id, second);
+
+        Ok(ids)
+    }
+
+    /// Replaces two finished entries with one spanning from the earlier start to the later end
+    ///
+    /// The merged entry gets a new id and keeps `a`'s details, with the union of both entries' tags,
+    /// pauses and history, plus a record of the merge. It's marked estimated if either entry was.
+    /// Any gap between the two entries counts towards the merged entry, so callers may want to
+    /// check for one first.
+    /// Returns the id of the merged entry
+    ///
+    /// # Errors
+    /// - Returns an error if either id has no entry, or both ids are the same
+    /// - Returns an error if either entry hasn't finished
+    pub fn merge_entries(&mut self, a: Uuid, b: Uuid) -> Result<Uuid> {
+        if a == b {
+            return Err(Error::MergeWithSelf(a));
+        }
+        let first = self.get_entry(a).ok_or(Error::EntryNotFound(a))?;
+        let second = self.get_entry(b).ok_or(Error::EntryNotFound(b))?;
+        let first_end = first.end_time.ok_or(Error::EntryNotFinished(a))?;
+        let second_end = second.end_time.ok_or(Error::EntryNotFinished(b))?;
+
+        let mut merged = first.clone();
+        merged.id = Uuid::new_v4().into_bytes();
+        merged.start_time = first.start_time.min(second.start_time);
+        merged.end_time = Some(first_end.max(second_end));
+        merged.estimated = first.estimated || second.estimated;
+        merged.history.extend_from_slice(&second.history);
+        merged.history.sort_by_key(|record| record.time);
+        merged.record(FieldChange::Merge(first.id, second.id));
+        for tag in &second.tags {
+            if !merged.tags.contains(tag) {
+                merged.tags.push(tag.clone());
+            }
         }
+        merged.pauses.extend_from_slice(&second.pauses);
+        merged.pauses.sort();
+
+        let id = Uuid::from_bytes(merged.id);
+        self.entries.remove(a.as_bytes());
+        self.entries.remove(b.as_bytes());
+        self.entries.insert(merged.id, merged);
+
+        Ok(id)
+    }
+
+    /// Gets the entry with the given id, if there is one
+    pub fn get_entry(&self, id: Uuid) -> Option<&Entry> {
+        self.entries.get(id.as_bytes())
+    }
+
+    /// Finds the one entry whose id starts with the given hex digits
+    ///
+    /// Matching ignores case and hyphens, so a full hyphenated id works too.
+    ///
+    /// # Errors
+    /// - Returns an error if the prefix is empty or isn't hex
+    /// - Returns an error if no entry's id starts with the prefix
+    /// - Returns an error if more than one entry's id starts with the prefix
+    pub fn find_by_prefix(&self, prefix: &str) -> Result<&Entry> {
+        let digits = prefix.replace('-', "").to_ascii_lowercase();
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::InvalidId(prefix.to_string()));
+        }
+
+        let mut matches = self
+            .entries
+            .values()
+            .filter(|entry| entry.uuid().simple().to_string().starts_with(&digits));
+
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Ok(entry),
+            (Some(_), Some(_)) => Err(Error::AmbiguousId(prefix.to_string())),
+            (None, _) => Err(Error::NoMatchingId(prefix.to_string())),
+        }
+    }
+
+    /// Iterates over all entries in no particular order, running or not
+    ///
+    /// Use [`EntryList::get_entries_in_order`] when the order matters.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.values()
+    }
+
+    /// Gets the number of entries in this list
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether this list has no entries at all
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
 
     /// Gets the current entry, if any
-    pub fn current_entry(&self) -> Option<&Entry> {
+    ///
+    /// # Errors
+    /// - Returns an error if the current entry is missing from the list
+    pub fn current_entry(&self) -> Result<Option<&Entry>> {
         self.current_entry
-            .map(|id| self.entries.get(&id).expect(NO_CURRENT_ENTRY_MESSAGE))
+            .map(|id| {
+                self.entries
+                    .get(&id)
+                    .ok_or(Error::CorruptState(Uuid::from_bytes(id)))
+            })
+            .transpose()
+    }
+
+    /// Gets the id of the current entry, if any, without looking the entry up
+    pub fn current_entry_id(&self) -> Option<Uuid> {
+        self.current_entry.map(Uuid::from_bytes)
+    }
+
+    /// Gets the current entry mutably, if any
+    fn current_entry_mut(&mut self) -> Result<Option<&mut Entry>> {
+        match self.current_entry {
+            Some(id) => self
+                .entries
+                .get_mut(&id)
+                .map(Some)
+                .ok_or(Error::CorruptState(Uuid::from_bytes(id))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a EntryList {
+    type Item = &'a Entry;
+    type IntoIter = std::collections::hash_map::Values<'a, Bytes, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.values()
+    }
+}
+
+/// Serializes entries in the order they started, rather than the [`HashMap`]'s arbitrary order
+pub(crate) fn serialize_in_order<S>(
+    entries: &HashMap<Bytes, Entry>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut sorted: Vec<_> = entries.values().collect();
+    sorted.sort_by_key(|entry| (entry.start_time, entry.id));
+    serializer.collect_map(sorted.into_iter().map(|entry| (entry.id, entry)))
+}
+
+/// Gets the path of a file kept next to a data file, named by appending `suffix` to the data file's name
+pub(crate) fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut sidecar = OsString::from(path.as_os_str());
+    sidecar.push(suffix);
+    sidecar.into()
+}
+
+/// Suffix added to a file's path to get the temporary file it's written to before being renamed into place
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Writes `bytes` to a temporary file next to `path`, then renames it over `path`
+///
+/// Renaming within a directory is atomic, so readers see either the old contents or the new ones.
+/// If the rename fails anyway (e.g. the filesystem doesn't support it), this falls back to writing
+/// `path` directly.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temp_path = sidecar_path(path, TEMP_SUFFIX);
+
+    let mut temp = File::create(&temp_path)?;
+    temp.write_all(bytes)?;
+    temp.sync_all()?;
+    drop(temp);
+
+    if fs::rename(&temp_path, path).is_err() {
+        fs::write(path, bytes)?;
+        fs::remove_file(&temp_path)?;
     }
+
+    Ok(())
+}
+
+/// Gets the tag that `tag` is an alias of, or `tag` itself if it isn't an alias
+pub(crate) fn resolve_alias<'a>(aliases: &'a BTreeMap<String, String>, tag: &'a str) -> &'a str {
+    aliases.get(tag).map_or(tag, String::as_str)
 }
@@ -10,6 +10,96 @@ pub enum Error {
 
     #[error("Failure serializing/deserializing entries")]
     Serialize(#[from] ron::error::Error),
+
+    #[error("Failure reading/writing CSV")]
+    Csv(#[from] csv::Error),
+
+    #[error("Failure writing JSON")]
+    Json(#[from] serde_json::Error),
+
+    #[error("\"{0}\" isn't a date and time like \"2024-01-15 09:00:00\"")]
+    InvalidDateTime(String),
+
+    #[error("Entry falls outside the configured work hours")]
+    OutsideWorkHours,
+
+    #[error("Entry starts in the future")]
+    FutureStartTime,
+
+    #[error("Entry ends before it starts")]
+    EndBeforeStart,
+
+    #[error("Entry duration of {0:?} is too long")]
+    DurationTooLong(std::time::Duration),
+
+    #[error("\"{0}\" isn't a valid entry id")]
+    InvalidId(String),
+
+    #[error("No entry has an id starting with \"{0}\"")]
+    NoMatchingId(String),
+
+    #[error("More than one entry has an id starting with \"{0}\", try more characters")]
+    AmbiguousId(String),
+
+    #[error("No entry has the id {0}")]
+    EntryNotFound(uuid::Uuid),
+
+    #[error("More than one entry has the id {0}")]
+    DuplicateId(uuid::Uuid),
+
+    #[error("Entry {0} is still running but isn't the current entry")]
+    UnfinishedEntry(uuid::Uuid),
+
+    #[error("Current entry {0} doesn't exist or has already finished")]
+    InvalidCurrentEntry(uuid::Uuid),
+
+    #[error("Entry {0} hasn't finished yet")]
+    EntryNotFinished(uuid::Uuid),
+
+    #[error("Split time isn't strictly inside the entry")]
+    SplitOutsideEntry,
+
+    #[error("Can't merge entry {0} with itself")]
+    MergeWithSelf(uuid::Uuid),
+
+    #[error("Entry {0} started after the one being reopened, which would overlap it")]
+    ReopenWouldOverlap(uuid::Uuid),
+
+    #[error("Current entry {0} is missing from the entry list, the data file may be corrupt")]
+    CorruptState(uuid::Uuid),
+
+    #[error("Entry {0} is already running, stop it first")]
+    EntryAlreadyRunning(uuid::Uuid),
+
+    #[error("Current entry is already paused")]
+    AlreadyPaused,
+
+    #[error("Current entry isn't paused")]
+    NotPaused,
+
+    #[error("Data file is in format version {0}, which is newer than this version of atomichron supports")]
+    UnsupportedVersion(u32),
+
+    #[error("Data file doesn't match its recorded checksum, so it may be corrupt or edited by hand (see `atomichron verify`)")]
+    IntegrityCheckFailed,
+
+    #[error("Data file has {0} problem(s), see `atomichron check`")]
+    ValidationFailed(usize),
+
+    #[error("Entry list was opened read-only")]
+    ReadOnly,
+
+    #[error("Data file is in use by another atomichron process")]
+    Locked,
+
+    #[error("No profile named \"{0}\" is configured")]
+    UnknownProfile(String),
+
+    #[error("\"{0}\" isn't a known time zone, try a name like \"Europe/Berlin\"")]
+    UnknownTimeZone(String),
+
+    #[error("\"{0}\" isn't a valid time format")]
+    InvalidTimeFormat(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,353 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    time::{Duration, SystemTime},
+};
+
+use crate::{aggregate::week_label, errors::Result, format_hours_minutes, Entry, EntryList, Error};
+
+/// Column names written as the first row of a CSV export
+const CSV_HEADERS: [&str; 7] = [
+    "id",
+    "project",
+    "description",
+    "tags",
+    "start_time",
+    "end_time",
+    "duration_seconds",
+];
+
+/// A dimension that entries can be grouped along in a pivot table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotAxis {
+    /// The local day an entry started on, e.g. `2024-01-15`
+    Day,
+    /// The ISO week an entry started in, e.g. `2024-W03`
+    Week,
+    /// The entry's project. Entries without one fall under [`EntryList::no_project_label`].
+    Project,
+    /// The entry's tags, with [aliases](EntryList::set_tag_aliases) folded in. Entries count fully
+    /// towards each of their tags, and are left out if they have none.
+    Tag,
+}
+
+impl PivotAxis {
+    /// Gets the name of this axis, used as the corner header of a pivot table
+    fn name(self) -> &'static str {
+        match self {
+            PivotAxis::Day => "day",
+            PivotAxis::Week => "week",
+            PivotAxis::Project => "project",
+            PivotAxis::Tag => "tag",
+        }
+    }
+
+    /// Gets the values an entry falls under along this axis
+    fn keys(self, list: &EntryList, entry: &Entry) -> Vec<String> {
+        let start = DateTime::<Local>::from(entry.start_time());
+
+        match self {
+            PivotAxis::Day => vec![start.format("%Y-%m-%d").to_string()],
+            PivotAxis::Week => vec![week_label(entry)],
+            PivotAxis::Project => vec![list.project_label(entry).to_string()],
+            PivotAxis::Tag => list.tag_labels(entry),
+        }
+    }
+}
+
+impl Entry {
+    /// Serializes this entry as a single line of compact JSON, with the same fields as the data file
+    ///
+    /// # Errors
+    /// - Returns an error if the entry can't be serialized
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(Error::from)
+    }
+}
+
+impl EntryList {
+    /// Writes entries as CSV to the writer provided, oldest first
+    ///
+    /// If `range` is given, only entries within it are written (see [`EntryList::get_entries_in_range`]).
+    /// Tags are joined with `;` and timestamps are written as RFC 3339. Running entries have empty
+    /// `end_time` and `duration_seconds` columns. If `headers` is false, the column header row is omitted.
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong writing the output
+    pub fn export_csv<W>(
+        &self,
+        writer: W,
+        headers: bool,
+        range: Option<(SystemTime, SystemTime)>,
+    ) -> Result<()>
+    where
+        W: Write,
+    {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        if headers {
+            csv_writer.write_record(CSV_HEADERS)?;
+        }
+
+        for entry in self.entries_for_export(range) {
+            let (end_time, duration) = match (entry.end_time(), entry.duration()) {
+                (Some(end_time), Some(duration)) => {
+                    (format_rfc3339(end_time), duration.as_secs().to_string())
+                }
+                _ => (String::new(), String::new()),
+            };
+
+            csv_writer.write_record([
+                entry.uuid().to_string(),
+                entry.project().clone().unwrap_or_default(),
+                entry.description().clone().unwrap_or_default(),
+                entry.tags().join(";"),
+                format_rfc3339(entry.start_time()),
+                end_time,
+                duration,
+            ])?;
+        }
+
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes entries as a Markdown list to the writer provided, grouped by local day, oldest first
+    ///
+    /// If `range` is given, only entries within it are written (see [`EntryList::get_entries_in_range`]).
+    /// Each entry shows its duration, and each day ends with a total line. Running entries are
+    /// counted up to now.
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong writing the output
+    pub fn export_markdown<W>(
+        &self,
+        mut writer: W,
+        range: Option<(SystemTime, SystemTime)>,
+    ) -> Result<()>
+    where
+        W: Write,
+    {
+        let mut current_day = None;
+        let mut day_total = Duration::ZERO;
+
+        for entry in self.entries_for_export(range) {
+            let start = DateTime::<Local>::from(entry.start_time());
+            let day = start.date_naive();
+
+            if current_day != Some(day) {
+                if current_day.is_some() {
+                    writeln!(writer, "\n**Total: {}**\n", format_hours_minutes(day_total))?;
+                }
+                writeln!(writer, "## {}\n", start.format("%A, %Y-%m-%d"))?;
+
+                current_day = Some(day);
+                day_total = Duration::ZERO;
+            }
+
+            let duration = entry.elapsed();
+            day_total += duration;
+
+            write!(
+                writer,
+                "- {}–{} **{}**",
+                start.format("%H:%M"),
+                match entry.end_time() {
+                    Some(end_time) => DateTime::<Local>::from(end_time).format("%H:%M").to_string(),
+                    None => "now".to_string(),
+                },
+                self.project_label(entry),
+            )?;
+            if let Some(description) = entry.description() {
+                write!(writer, ": {}", description)?;
+            }
+            for tag in entry.tags() {
+                write!(writer, " `{}`", tag)?;
+            }
+            writeln!(writer, " ({})", format_hours_minutes(duration))?;
+        }
+
+        if current_day.is_some() {
+            writeln!(writer, "\n**Total: {}**", format_hours_minutes(day_total))?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders the entries which started within `[from, to)` as Markdown tables, one per local day
+    ///
+    /// Each row shows an entry's project, description, tags (as inline code) and duration, and each
+    /// table ends with a bold total row. Entries without a project show
+    /// [`EntryList::no_project_label`]. Running entries are counted up to now.
+    pub fn report_markdown(&self, from: SystemTime, to: SystemTime) -> String {
+        use std::fmt::Write as _;
+
+        let mut days: BTreeMap<_, Vec<&Entry>> = BTreeMap::new();
+        for entry in self.get_entries_in_range(from, to, true) {
+            if !self.counts_for_billing(entry) {
+                continue;
+            }
+            let day = DateTime::<Local>::from(entry.start_time()).date_naive();
+            days.entry(day).or_default().push(entry);
+        }
+
+        let mut report = String::new();
+        for (day, entries) in days {
+            if !report.is_empty() {
+                report.push('\n');
+            }
+            // Writing to a String can't fail
+            let _ = writeln!(report, "## {}\n", day.format("%A, %Y-%m-%d"));
+            report.push_str("| Project | Description | Tags | Duration |\n");
+            report.push_str("| ------- | ----------- | ---- | -------: |\n");
+
+            let mut total = Duration::ZERO;
+            for entry in entries {
+                total += entry.elapsed();
+                let tags: Vec<_> = entry
+                    .tags()
+                    .iter()
+                    .map(|tag| format!("`{}`", tag))
+                    .collect();
+                let _ = writeln!(
+                    report,
+                    "| {} | {} | {} | {} |",
+                    escape_cell(self.project_label(entry)),
+                    escape_cell(entry.description().as_deref().unwrap_or_default()),
+                    escape_cell(&tags.join(" ")),
+                    format_hours_minutes(entry.elapsed()),
+                );
+            }
+            let _ = writeln!(
+                report,
+                "| **Total** | | | **{}** |",
+                format_hours_minutes(total)
+            );
+        }
+
+        report
+    }
+
+    /// Writes entries as newline-delimited JSON to the writer provided, one entry per line, oldest first
+    ///
+    /// If `range` is given, only entries within it are written (see [`EntryList::get_entries_in_range`]).
+    /// Each line is an [`Entry::to_json`] object, so output can be streamed or appended to.
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong writing the output
+    pub fn export_json_lines<W>(
+        &self,
+        mut writer: W,
+        range: Option<(SystemTime, SystemTime)>,
+    ) -> Result<()>
+    where
+        W: Write,
+    {
+        for entry in self.entries_for_export(range) {
+            writeln!(writer, "{}", entry.to_json()?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes entries as a JSON array to the writer provided, oldest first
+    ///
+    /// If `range` is given, only entries within it are written (see [`EntryList::get_entries_in_range`]).
+    /// The output can be read back with [`EntryList::import_json`].
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong writing the output
+    pub fn export_json<W>(
+        &self,
+        mut writer: W,
+        range: Option<(SystemTime, SystemTime)>,
+    ) -> Result<()>
+    where
+        W: Write,
+    {
+        serde_json::to_writer_pretty(&mut writer, &self.entries_for_export(range))?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    /// Builds a pivot table of tracked hours as CSV, with one row per value of `rows` and one column
+    /// per value of `cols`
+    ///
+    /// Each cell is the total hours (to two decimal places) of finished entries falling under both
+    /// its row and column, or `0` if there are none. Rows and columns are sorted. As in
+    /// [`EntryList::export_csv`], `range` limits which entries count, and the header row naming the
+    /// columns is omitted if `headers` is false.
+    ///
+    /// # Errors
+    /// - Returns an error if anything goes wrong writing the CSV
+    pub fn export_pivot_csv(
+        &self,
+        rows: PivotAxis,
+        cols: PivotAxis,
+        headers: bool,
+        range: Option<(SystemTime, SystemTime)>,
+    ) -> Result<String> {
+        let mut cells: BTreeMap<(String, String), Duration> = BTreeMap::new();
+        let mut row_keys = BTreeSet::new();
+        let mut col_keys = BTreeSet::new();
+
+        for entry in self.entries_for_export(range) {
+            let duration = match entry.duration() {
+                Some(duration) => duration,
+                None => continue,
+            };
+
+            for row in rows.keys(self, entry) {
+                for col in cols.keys(self, entry) {
+                    row_keys.insert(row.clone());
+                    col_keys.insert(col.clone());
+                    *cells.entry((row.clone(), col)).or_default() += duration;
+                }
+            }
+        }
+
+        let mut csv_writer = csv::Writer::from_writer(Vec::new());
+
+        if headers {
+            let mut header = vec![rows.name().to_string()];
+            header.extend(col_keys.iter().cloned());
+            csv_writer.write_record(&header)?;
+        }
+
+        for row in row_keys {
+            let mut record = vec![row.clone()];
+            for col in &col_keys {
+                record.push(match cells.get(&(row.clone(), col.clone())) {
+                    Some(duration) => format!("{:.2}", duration.as_secs_f64() / 3600.0),
+                    None => "0".to_string(),
+                });
+            }
+            csv_writer.write_record(&record)?;
+        }
+
+        let bytes = csv_writer
+            .into_inner()
+            .map_err(|e| Error::File(e.into_error()))?;
+        Ok(String::from_utf8(bytes).expect("CSV built from strings is valid UTF-8"))
+    }
+
+    /// Gets the entries to export, oldest first, optionally restricted to a range
+    fn entries_for_export(&self, range: Option<(SystemTime, SystemTime)>) -> Vec<&Entry> {
+        match range {
+            Some((from, to)) => self.get_entries_in_range(from, to, true),
+            None => self.get_entries_in_order(true),
+        }
+    }
+}
+
+/// Formats a timestamp as an RFC 3339 string in UTC
+fn format_rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Escapes text for a Markdown table cell, so pipes and newlines don't break the table
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
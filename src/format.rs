@@ -0,0 +1,95 @@
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Local, Utc,
+};
+use chrono_tz::Tz;
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use crate::{errors::Result, Error};
+
+/// Format that times are shown in unless configured otherwise, e.g. `2024-01-15 09:30`
+pub const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Time zone that times are shown in. Entries are always stored as UTC, whatever this is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayZone {
+    /// The system's time zone, which can be overridden with the `TZ` environment variable
+    #[default]
+    Local,
+    /// A zone from the IANA database, e.g. `Europe/Berlin` or `UTC`
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// Formats a time in this zone, e.g. with [`DEFAULT_TIME_FORMAT`]
+    ///
+    /// The format should have been checked with [`check_time_format`], as an invalid one panics.
+    pub fn format(self, time: SystemTime, format: &str) -> String {
+        let time = DateTime::<Utc>::from(time);
+        match self {
+            DisplayZone::Local => time.with_timezone(&Local).format(format).to_string(),
+            DisplayZone::Named(tz) => time.with_timezone(&tz).format(format).to_string(),
+        }
+    }
+}
+
+impl FromStr for DisplayZone {
+    type Err = Error;
+
+    /// Parses `local`, or the name of a zone from the IANA database
+    fn from_str(name: &str) -> Result<Self> {
+        if name.eq_ignore_ascii_case("local") {
+            return Ok(DisplayZone::Local);
+        }
+        name.parse()
+            .map(DisplayZone::Named)
+            .map_err(|_| Error::UnknownTimeZone(name.to_string()))
+    }
+}
+
+/// Checks that a strftime-style format string, e.g. `%d/%m %H:%M`, can be used to format times
+///
+/// # Errors
+/// - Returns an error if the format has an unknown or incomplete specifier
+pub fn check_time_format(format: &str) -> Result<()> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(Error::InvalidTimeFormat(format.to_string()));
+    }
+    Ok(())
+}
+
+/// Formats an ISO year and week number, e.g. `2024-W03`
+pub fn format_iso_week(year: i32, week: u32) -> String {
+    format!("{}-W{:02}", year, week)
+}
+
+/// Formats a duration as whole hours and minutes, e.g. `2h45m`, for totals where seconds don't
+/// matter
+///
+/// Unlike [`format_duration`], hours aren't rolled over into days.
+pub fn format_hours_minutes(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h{:02}m", minutes / 60, minutes % 60)
+}
+
+/// Formats a duration for people to read, e.g. `2h 15m 03s`
+///
+/// Leading units which are zero are left out, and anything over a day is counted in days, e.g.
+/// `1d 3h 00m 00s` rather than `27h 00m 00s`. Anything under a second is shown as `0s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, secs) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{}d {}h {:02}m {:02}s", days, hours, minutes, secs)
+    } else if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, secs)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, secs)
+    } else {
+        format!("{}s", secs)
+    }
+}
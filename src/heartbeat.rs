@@ -0,0 +1,103 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use crate::{entries::sidecar_path, errors::Result, Entry, EntryList};
+
+/// How long a running entry can go without a heartbeat before the machine is suspected of sleeping
+pub const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+
+/// Suffix added to a data file's path to get the file its heartbeats are kept in
+const HEARTBEATS_SUFFIX: &str = ".heartbeats";
+
+impl Entry {
+    /// Guesses how much of this entry the machine spent asleep, from heartbeats recorded while it ran
+    ///
+    /// Returns the time between the last heartbeat within this entry and its end (or now, if it's
+    /// running), if that's longer than [`SLEEP_GAP_THRESHOLD`]. Returns `None` if no heartbeats fall
+    /// within the entry, since there's then nothing to judge by.
+    pub fn suspected_sleep_gap(&self, heartbeats: &[SystemTime]) -> Option<Duration> {
+        let end_time = self.end_time().unwrap_or_else(SystemTime::now);
+        let last_heartbeat = heartbeats
+            .iter()
+            .filter(|&&heartbeat| self.start_time() <= heartbeat && heartbeat <= end_time)
+            .max()?;
+
+        let gap = end_time.duration_since(*last_heartbeat).unwrap_or_default();
+        (gap > SLEEP_GAP_THRESHOLD).then_some(gap)
+    }
+}
+
+impl EntryList {
+    /// Checks whether the running entry has been going for longer than `threshold`, e.g. because it
+    /// was left running overnight
+    ///
+    /// Time spent paused doesn't count. Returns false if nothing is running.
+    pub fn running_exceeds(&self, threshold: Duration) -> bool {
+        self.current_entry()
+            .ok()
+            .flatten()
+            .is_some_and(|entry| entry.end_time().is_none() && entry.elapsed() > threshold)
+    }
+}
+
+/// Records a heartbeat for the data file at the path provided, marking that its running entry is still active
+///
+/// Heartbeats are appended to a file next to the data file, at the same path with `.heartbeats` appended.
+///
+/// # Errors
+/// - Returns an error if anything goes wrong writing the file
+pub fn record_heartbeat<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(sidecar_path(path.as_ref(), HEARTBEATS_SUFFIX))?;
+    writeln!(file, "{}", secs)?;
+    Ok(())
+}
+
+/// Reads the heartbeats recorded for the data file at the path provided
+///
+/// A missing heartbeats file gives no heartbeats, and unreadable lines are skipped.
+///
+/// # Errors
+/// - Returns an error if anything goes wrong reading the file (other than not finding it)
+pub fn load_heartbeats<P>(path: P) -> Result<Vec<SystemTime>>
+where
+    P: AsRef<Path>,
+{
+    match fs::read_to_string(sidecar_path(path.as_ref(), HEARTBEATS_SUFFIX)) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Discards the heartbeats recorded for the data file at the path provided, e.g. once its entry stops
+///
+/// # Errors
+/// - Returns an error if anything goes wrong removing the file (other than it not existing)
+pub fn clear_heartbeats<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    match fs::remove_file(sidecar_path(path.as_ref(), HEARTBEATS_SUFFIX)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use uuid::Bytes;
+
+use crate::Entry;
+
+/// A single change made to an entry after it was created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditRecord {
+    /// When the change was made
+    pub time: SystemTime,
+
+    /// Which field changed, with its values before and after
+    pub change: FieldChange,
+}
+
+/// A field of an entry which changed, holding its old value then its new one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldChange {
+    /// The category was set or changed
+    Category(Option<String>, Option<String>),
+    /// The project was set or changed
+    Project(Option<String>, Option<String>),
+    /// The description was set or changed
+    Description(Option<String>, Option<String>),
+    /// The tags were replaced
+    Tags(Vec<String>, Vec<String>),
+    /// The entry was marked billable or not billable
+    Billable(bool, bool),
+    /// The entry was stopped, or its end time was moved (e.g. by rounding)
+    EndTime(Option<SystemTime>, Option<SystemTime>),
+    /// The entry was made by splitting the entry with this id at this time. The entry's earlier
+    /// history is that of the entry it was split from.
+    Split(Bytes, SystemTime),
+    /// The entry was made by merging the entries with these ids. The entry's earlier history is
+    /// that of both, in the order the changes were made.
+    Merge(Bytes, Bytes),
+}
+
+impl Entry {
+    /// Get the changes made to this entry since it was created, oldest first
+    ///
+    /// Entries saved before changes were recorded have an empty history.
+    pub fn history(&self) -> &[EditRecord] {
+        &self.history
+    }
+
+    /// Overwrites the details given as `Some`, recording each one that actually changes
+    pub(crate) fn update_details(
+        &mut self,
+        project: Option<String>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        category: Option<String>,
+        billable: Option<bool>,
+    ) {
+        if category.is_some() && category != self.category {
+            let old = std::mem::replace(&mut self.category, category.clone());
+            self.record(FieldChange::Category(old, category));
+        }
+        if project.is_some() && project != self.project {
+            let old = std::mem::replace(&mut self.project, project.clone());
+            self.record(FieldChange::Project(old, project));
+        }
+        if description.is_some() && description != self.description {
+            let old = std::mem::replace(&mut self.description, description.clone());
+            self.record(FieldChange::Description(old, description));
+        }
+        if let Some(tags) = tags.filter(|tags| *tags != self.tags) {
+            let old = std::mem::replace(&mut self.tags, tags.clone());
+            self.record(FieldChange::Tags(old, tags));
+        }
+        if let Some(billable) = billable.filter(|billable| *billable != self.billable) {
+            self.billable = billable;
+            self.record(FieldChange::Billable(!billable, billable));
+        }
+    }
+
+    /// Appends a change to this entry's history, timestamped now
+    pub(crate) fn record(&mut self, change: FieldChange) {
+        self.history.push(EditRecord {
+            time: SystemTime::now(),
+            change,
+        });
+    }
+}
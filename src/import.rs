@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    io::{BufRead, Read},
+};
+
+use crate::{errors::Result, Entry, EntryList, Error};
+
+/// What to do when an imported entry has the same id as one already in the list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Keep the existing entry exactly as it is, ignoring the imported one
+    #[default]
+    Skip,
+    /// Replace the existing entry with the imported one entirely
+    Overwrite,
+    /// Keep the existing entry, but add any tags it's missing from the imported one
+    MergeTags,
+}
+
+/// Counts of what happened to each entry in an import
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Entries with new ids, which were added as they were
+    pub added: usize,
+    /// Entries whose id was already in use, and which changed the existing entry
+    pub updated: usize,
+    /// Entries which were left out, either because of [`OnConflict::Skip`] or because they were still running
+    pub skipped: usize,
+}
+
+impl EntryList {
+    /// Imports entries in the format written by [`EntryList::export_json_lines`], one JSON object per line
+    ///
+    /// Blank lines are ignored. See [`EntryList::import_entries`] for how the entries are added.
+    ///
+    /// # Errors
+    /// - Returns an error if a line can't be read or isn't a valid entry
+    /// - Returns an error if an entry ends before it starts
+    pub fn import_json_lines<R>(
+        &mut self,
+        reader: R,
+        on_conflict: OnConflict,
+    ) -> Result<ImportSummary>
+    where
+        R: BufRead,
+    {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        self.import_entries(entries, on_conflict)
+    }
+
+    /// Imports entries from a JSON array, as written by [`EntryList::export_json`]
+    ///
+    /// See [`EntryList::import_entries`] for how the entries are added.
+    ///
+    /// # Errors
+    /// - Returns an error if the input can't be read or isn't an array of valid entries
+    /// - Returns an error if an entry ends before it starts
+    pub fn import_json<R>(&mut self, reader: R, on_conflict: OnConflict) -> Result<ImportSummary>
+    where
+        R: Read,
+    {
+        let entries = serde_json::from_reader(reader)?;
+        self.import_entries(entries, on_conflict)
+    }
+
+    /// Adds entries from elsewhere to this list, resolving ids already in use with `on_conflict`
+    ///
+    /// Entries which are still running are skipped, since only the current entry may be running.
+    /// For the same reason, the current entry is never changed by an import. Every entry is checked
+    /// before any are added, so on an error the list is left as it was.
+    ///
+    /// # Errors
+    /// - Returns an error if an entry ends before it starts
+    pub fn import_entries(
+        &mut self,
+        entries: Vec<Entry>,
+        on_conflict: OnConflict,
+    ) -> Result<ImportSummary> {
+        if entries.iter().any(|entry| {
+            entry
+                .end_time()
+                .is_some_and(|end_time| end_time < entry.start_time())
+        }) {
+            return Err(Error::EndBeforeStart);
+        }
+
+        let mut summary = ImportSummary::default();
+        for entry in entries {
+            if entry.end_time().is_none() || self.current_entry == Some(entry.id()) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            match (self.entries.get_mut(&entry.id()), on_conflict) {
+                (None, _) => {
+                    summary.added += 1;
+                    self.entries.insert(entry.id(), entry);
+                }
+                (Some(_), OnConflict::Skip) => summary.skipped += 1,
+                (Some(existing), OnConflict::Overwrite) => {
+                    summary.updated += 1;
+                    *existing = entry;
+                }
+                (Some(existing), OnConflict::MergeTags) => {
+                    let id = existing.uuid();
+                    let known: HashSet<_> = existing.tags.iter().cloned().collect();
+                    let missing: Vec<_> = entry
+                        .tags
+                        .into_iter()
+                        .filter(|tag| !known.contains(tag))
+                        .collect();
+
+                    if missing.is_empty() {
+                        summary.skipped += 1;
+                    } else {
+                        // Goes through add_tags so the change shows up in the entry's history
+                        summary.updated += 1;
+                        self.add_tags(id, missing)?;
+                    }
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
@@ -1,6 +1,38 @@
 pub mod aggregate;
+mod archive;
+mod billing;
+mod builder;
+mod bulk;
+mod checksum;
+mod config;
 mod entries;
 mod errors;
+mod export;
+mod format;
+mod heartbeat;
+mod history;
+mod import;
+mod lock;
+mod status;
+mod toggl;
+mod validate;
+mod version;
 
+pub use archive::*;
+pub use billing::*;
+pub use builder::*;
+pub use bulk::*;
+pub use checksum::*;
+pub use config::*;
 pub use entries::*;
 pub use errors::*;
+pub use export::*;
+pub use format::*;
+pub use heartbeat::*;
+pub use history::*;
+pub use import::*;
+pub use lock::*;
+pub use status::*;
+pub use toggl::*;
+pub use validate::*;
+pub use version::*;
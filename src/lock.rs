@@ -0,0 +1,51 @@
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    path::Path,
+};
+
+use crate::{entries::sidecar_path, errors::Result, Error};
+
+/// Suffix added to a data file's path to get the file it's locked through
+const LOCK_SUFFIX: &str = ".lock";
+
+/// An exclusive lock on a data file, held until this is dropped
+///
+/// The lock is taken on a separate file next to the data file, at the same path with `.lock`
+/// appended, since saving replaces the data file itself. Locks are advisory, so they only keep out
+/// other processes which lock the file too.
+#[derive(Debug)]
+pub struct DataFileLock {
+    _file: File,
+}
+
+impl DataFileLock {
+    /// Locks the data file at the path provided, for the whole of a load, modify and save
+    ///
+    /// If another process holds the lock, this waits for it to be released when `wait` is set, and
+    /// fails straight away otherwise.
+    ///
+    /// # Errors
+    /// - Returns an error if another process holds the lock and `wait` isn't set
+    /// - Returns an error if the lock file can't be created or locked
+    pub fn acquire<P>(path: P, wait: bool) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(sidecar_path(path.as_ref(), LOCK_SUFFIX))?;
+
+        if wait {
+            file.lock()?;
+        } else {
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => return Err(Error::Locked),
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+        Ok(DataFileLock { _file: file })
+    }
+}
@@ -1,12 +1,77 @@
-use clap::{Args, Parser, Subcommand};
-use std::fmt::Display;
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use directories::ProjectDirs;
+use owo_colors::{AnsiColors, OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    env,
+    error::Error as _,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufReader, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    time::{Duration, SystemTime},
+};
 
-use atomichron::EntryList;
+use atomichron::{
+    aggregate::{day_range, local_time, page, rank, total_of, AverageBasis},
+    archive_path, check_time_format, clear_heartbeats, earnings, format_duration,
+    format_hours_minutes, format_iso_week, load_heartbeats, parse_increment, record_heartbeat,
+    round_duration, Config, DataFileLock, DisplayZone, Entry, EntryList, Error, ImportSummary,
+    Integrity, OnConflict, PivotAxis, RoundMode, StopOutcome, DEFAULT_TIME_FORMAT,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[command(after_help = EXIT_CODES_HELP)]
 struct Cli {
+    /// Read the config from this file instead of config.ron in the platform's config directory
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Use the data file of a profile defined in the config
+    #[arg(long, global = true, env = "ATOMICHRON_PROFILE")]
+    profile: Option<String>,
+
+    /// Use this data file, overriding any profile. Without this or a profile, the ATOMICHRON_FILE
+    /// environment variable is used, then a file in the platform's data directory.
+    #[arg(long = "file", global = true)]
+    data_file: Option<PathBuf>,
+
+    /// Print the results of start, stop, status, log, report and summary as JSON, for scripts
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Refuse to run if the data file has problems, such as entries ending before they start,
+    /// instead of only warning about them
+    #[arg(long, global = true)]
+    strict_validation: bool,
+
+    /// If another command is changing the data file, wait for it to finish instead of failing
+    #[arg(long, global = true)]
+    wait: bool,
+
+    /// Never color the output. It's also left plain when NO_COLOR is set, or when it isn't going
+    /// to a terminal.
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Time zone to show times in, e.g. "Europe/Berlin" or "UTC". Defaults to the timezone config
+    /// option, then the local time zone (which follows TZ).
+    #[arg(long, global = true)]
+    tz: Option<String>,
+
+    /// strftime-style format to show times in, e.g. "%d/%m %H:%M". Defaults to the time_format
+    /// config option, then "%Y-%m-%d %H:%M".
+    #[arg(long, global = true)]
+    time_format: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -14,15 +79,157 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Starts a new time entry. If the timer is currently running, the current entry will be stopped and the new one started.
-    Start(EntryInfo),
+    Start(StartArgs),
+    /// Starts an entry, runs a command to completion, then stops the entry. The command's exit
+    /// code is passed on, e.g. `atomichron track -p build -- cargo build`.
+    Track(TrackArgs),
     /// Stops the current time entry. If project or description are provided, they will overwrite any project or description set when the timer was started.
     Stop(EntryInfo),
     /// Stops the current time entry, then discards it.
     Clear,
+    /// Starts a new entry with the same details as the most recently finished one.
+    Continue,
+    /// Starts a finished entry running again, keeping its start time, e.g. after stopping it by
+    /// accident. Entries which others have started after can't be reopened.
+    Reopen {
+        /// Id of the entry to reopen, or just enough of its start to be unique, as shown by `log`.
+        /// Defaults to the most recently finished entry.
+        id: Option<String>,
+    },
+    /// Pauses the current entry, so time until it's resumed isn't counted.
+    Pause,
+    /// Resumes the current entry after a pause.
+    Resume,
+    /// Adds a finished entry, e.g. to log time after the fact, without touching the running timer.
+    Add(AddArgs),
+    /// Changes the project, description, tags or category of any entry, finished or running, by its id.
+    Edit(EditArgs),
+    /// Adds tags to or removes tags from any entry, leaving its other tags alone.
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Restores the entry most recently removed by `clear` or by `delete` with an id. Entries
+    /// deleted with --project, --tag or --before can't be restored.
+    Undo,
+    /// Deletes any entry, finished or running, by its id, or every entry matching --project, --tag
+    /// or --before. Deleting by filter asks for confirmation first, and can't be undone.
+    Delete(DeleteArgs),
+    /// Splits a finished entry in two at the given time, e.g. when one block was really two tasks.
+    Split {
+        /// Id of the entry to split, or just enough of its start to be unique, as shown by `log`
+        id: String,
+
+        /// Local time to split the entry at, e.g. "2024-01-15 11:00"
+        #[arg(value_parser = parse_local_datetime)]
+        at: SystemTime,
+    },
+    /// Merges two finished entries into one, keeping the first one's project and description.
+    Merge {
+        /// Id of the entry whose details are kept, or just enough of its start to be unique
+        first: String,
+
+        /// Id of the entry merged into it
+        second: String,
+    },
     /// Displays the current status.
-    Status,
+    Status(StatusArgs),
     /// Logs all entries, grouped by day.
-    Log,
+    Log(LogArgs),
+    /// Exports all entries to stdout.
+    Export(ExportArgs),
+    /// Displays statistics about all entries.
+    Stats(StatsArgs),
+    /// Ranks projects (or categories) by the time spent on them.
+    Report(ReportArgs),
+    /// Shows the total time tracked on each day.
+    Summary(SummaryArgs),
+    /// Lists every tag with how many entries have it, counting configured aliases as the tag they stand for.
+    Tags(TagsArgs),
+    /// Lists every project used, one per line.
+    Projects,
+    /// Finds entries whose description or project contains some text, ignoring case.
+    Search {
+        /// Text to look for, e.g. "bug fix"
+        query: String,
+    },
+    /// Lists the descriptions previously used for a project, most recent first.
+    Tasks(TasksArgs),
+    /// Imports entries from a file written by `export --format json` or `--format json-lines`.
+    Import(ImportArgs),
+    /// Replaces a day's finished entries with a single summary entry (or one per project).
+    Collapse(CollapseArgs),
+    /// Moves finished entries from before a date into an archive file, keeping the data file small.
+    Archive(ArchiveArgs),
+    /// Lists the configured profiles, marking the active one.
+    Profiles,
+    /// Checks the data file against the checksum recorded when it was last saved. After editing the
+    /// file by hand, `--accept` records a new checksum so it loads again.
+    Verify {
+        /// Accept the data file as it is, e.g. after editing it by hand, and record a new checksum
+        #[arg(long)]
+        accept: bool,
+    },
+    /// Lists the stretches of a day not covered by any entry, e.g. to fill in a timesheet.
+    Gaps(GapsArgs),
+    /// Lists problems in the data file, like entries ending before they start, and entries whose
+    /// times overlap, treating the running entry as ending now.
+    Check,
+    /// Prints a completion script for the given shell, e.g. `atomichron completions bash`.
+    #[command(hide = true)]
+    Completions {
+        /// Shell to print the script for
+        shell: Shell,
+    },
+    /// Records that the running entry is still active. Run this every minute or so (e.g. from cron) so that stopping can detect time spent asleep.
+    Heartbeat,
+}
+
+#[derive(Args)]
+struct StartArgs {
+    #[command(flatten)]
+    info: EntryInfo,
+
+    /// Refuse to start the entry outside the configured work hours, instead of just warning
+    #[arg(long)]
+    strict: bool,
+
+    /// Refuse to start the entry while another is running, instead of stopping that one first
+    #[arg(long)]
+    no_stop: bool,
+
+    /// Start the entry at this local time instead of now, e.g. "2024-01-15 09:00"
+    #[arg(long, value_parser = parse_local_datetime)]
+    at: Option<SystemTime>,
+
+    /// Allow `--at` to be in the future
+    #[arg(long, requires = "at")]
+    allow_future: bool,
+
+    /// Mark the entry's times as only approximate, e.g. when logging from memory
+    #[arg(long, requires = "at")]
+    estimated: bool,
+}
+
+#[derive(Args)]
+struct TrackArgs {
+    /// Optional project for this entry
+    #[arg(short, long)]
+    project: Option<String>,
+
+    /// Optional description for this entry. Defaults to the command being run.
+    #[arg(short, long)]
+    description: Option<String>,
+
+    /// Optional list of tags for this entry, separated by commas
+    #[arg(short, long, value_delimiter = ',')]
+    tags: Vec<String>,
+
+    /// Optional category for this entry, grouping several projects (e.g. a client)
+    #[arg(short, long)]
+    category: Option<String>,
+
+    /// Command to run, and its arguments
+    #[arg(last = true, required = true)]
+    command: Vec<String>,
 }
 
 #[derive(Args)]
@@ -36,6 +243,597 @@ struct EntryInfo {
     /// Optional list of tags for this entry, separated by commas
     #[arg(short, long, value_delimiter = ',')]
     tags: Vec<String>,
+
+    /// Optional category for this entry, grouping several projects (e.g. a client)
+    #[arg(short, long)]
+    category: Option<String>,
+
+    #[command(flatten)]
+    billable: BillableArgs,
+}
+
+/// Whether an entry is billable, for the commands which can set it
+#[derive(Args)]
+struct BillableArgs {
+    /// Mark the entry as billable, which new entries are unless marked otherwise
+    #[arg(long, conflicts_with = "no_billable")]
+    billable: bool,
+
+    /// Mark the entry as not billable, e.g. for internal meetings or admin
+    #[arg(long)]
+    no_billable: bool,
+}
+
+impl BillableArgs {
+    /// Gets whether the entry should be billable, if either flag was given
+    fn value(&self) -> Option<bool> {
+        match (self.billable, self.no_billable) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Args)]
+struct AddArgs {
+    #[command(flatten)]
+    info: EntryInfo,
+
+    /// Local time the entry started, e.g. "2024-01-15 09:00"
+    #[arg(long, value_parser = parse_local_datetime)]
+    from: SystemTime,
+
+    /// Local time the entry ended, e.g. "2024-01-15 10:30"
+    #[arg(long, value_parser = parse_local_datetime)]
+    to: SystemTime,
+
+    /// Allow `--from` to be in the future
+    #[arg(long)]
+    allow_future: bool,
+
+    /// Mark the entry's times as only approximate, e.g. when logging from memory
+    #[arg(long)]
+    estimated: bool,
+}
+
+#[derive(Args)]
+struct EditArgs {
+    /// Id of the entry to edit, or just enough of its start to be unique, as shown by `log`
+    id: String,
+
+    /// New project for the entry
+    #[arg(short, long)]
+    project: Option<String>,
+
+    /// New description for the entry
+    #[arg(short, long)]
+    description: Option<String>,
+
+    /// New list of tags for the entry, separated by commas, replacing the old ones
+    #[arg(short, long, value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+
+    /// Remove all of the entry's tags
+    #[arg(long, conflicts_with = "tags")]
+    clear_tags: bool,
+
+    /// New category for the entry
+    #[arg(short, long)]
+    category: Option<String>,
+
+    #[command(flatten)]
+    billable: BillableArgs,
+}
+
+#[derive(Subcommand)]
+enum TagCommand {
+    /// Adds tags to an entry, skipping any it already has
+    Add {
+        /// Id of the entry to tag, or just enough of its start to be unique, as shown by `log`
+        id: String,
+
+        /// Tags to add, separated by spaces or commas
+        #[arg(required = true, value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+    /// Removes tags from an entry
+    #[command(alias = "remove")]
+    Rm {
+        /// Id of the entry to untag, or just enough of its start to be unique, as shown by `log`
+        id: String,
+
+        /// Tags to remove, separated by spaces or commas
+        #[arg(required = true, value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Args)]
+struct StatusArgs {
+    /// Keep the status on screen, updating every second. Press `p` to pause or resume, `s` to stop
+    /// the entry, `q` to quit.
+    #[cfg(feature = "watch")]
+    #[arg(short, long)]
+    watch: bool,
+
+    /// Also show the last N completed entries, most recent first
+    #[arg(short, long, value_name = "N", default_value_t = 0)]
+    recent: usize,
+
+    /// Warn if the running entry has gone on longer than this, e.g. "10h" (overrides the config)
+    #[arg(long, value_parser = parse_increment)]
+    warn_after: Option<Duration>,
+
+    /// How to show the status. JSON is a single object, for shell prompts and status bars
+    #[arg(long, value_enum, default_value_t = StatusFormat::Text)]
+    format: StatusFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatusFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+struct LogArgs {
+    /// Show the currently running entry first, followed by the rest
+    #[arg(long)]
+    running_first: bool,
+
+    /// Only show entries for this project. Supports `*` and `?` wildcards, e.g. "Client/*"
+    #[arg(short, long)]
+    project: Option<String>,
+
+    /// Only show entries without a project
+    #[arg(long, conflicts_with = "project")]
+    no_project: bool,
+
+    /// Only show entries with this tag (exact and case-sensitive)
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Only show entries lasting at least this many minutes (running entries count up to now)
+    #[arg(long)]
+    min_duration: Option<u64>,
+
+    /// Only show entries lasting at most this many minutes (running entries count up to now)
+    #[arg(long)]
+    max_duration: Option<u64>,
+
+    /// Count the running entry's time so far in the total, which is otherwise left out
+    #[arg(long)]
+    live: bool,
+
+    /// Show at most this many entries, after filtering, e.g. `--limit 10` for the ten most recent
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Skip this many entries, after filtering, before showing any
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+
+    /// Show each entry's full id, instead of just enough of its start to refer to it
+    #[arg(long)]
+    long_ids: bool,
+
+    #[command(flatten)]
+    range: RangeArgs,
+}
+
+/// Arguments for `delete`
+#[derive(Args)]
+struct DeleteArgs {
+    /// Id of the entry to delete, or just enough of its start to be unique, as shown by `log`
+    #[arg(
+        required_unless_present_any = ["project", "tag", "before"],
+        conflicts_with_all = ["project", "tag", "before"]
+    )]
+    id: Option<String>,
+
+    /// Delete every entry for this project instead. Supports `*` and `?` wildcards, e.g. "Client/*"
+    #[arg(long)]
+    project: Option<String>,
+
+    /// Delete every entry with this tag instead (exact and case-sensitive)
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Delete every entry starting before this instead, e.g. "2024-01-15" or "2024-01-15 09:00".
+    /// Combined with --project or --tag, only entries matching all of them are deleted.
+    #[arg(long, value_parser = parse_date_bound)]
+    before: Option<DateBound>,
+
+    /// Also delete the running entry if it matches, rather than skipping it
+    #[arg(long)]
+    force: bool,
+}
+
+/// Date range shared by the commands which query entries
+#[derive(Args)]
+struct RangeArgs {
+    /// Only count entries starting at or after this, e.g. "2024-01-15", "2024-01-15 09:00" or "yesterday"
+    #[arg(long, value_parser = parse_date_bound)]
+    from: Option<DateBound>,
+
+    /// Only count entries starting before the end of this day (or before this time, if one's given)
+    #[arg(long, value_parser = parse_date_bound)]
+    to: Option<DateBound>,
+
+    /// Only count entries starting in the current day, week, month or year, in local time
+    #[arg(long, value_enum, conflicts_with_all = ["from", "to"])]
+    period: Option<Period>,
+}
+
+impl RangeArgs {
+    /// Gets the selected range as `[since, until)`, which defaults to everything up to the end of today
+    fn range(&self) -> (SystemTime, SystemTime) {
+        if let Some(period) = self.period {
+            let (first, last) = period.days(Local::now().date_naive());
+            return (day_range(first).0, day_range(last).1);
+        }
+
+        let since = match self.from {
+            Some(DateBound::Day(day)) => day_range(day).0,
+            Some(DateBound::Time(time)) => time,
+            None => SystemTime::UNIX_EPOCH,
+        };
+        let until = match self.to {
+            Some(DateBound::Day(day)) => day_range(day).1,
+            Some(DateBound::Time(time)) => time,
+            None => day_range(Local::now().date_naive()).1,
+        };
+
+        (since, until)
+    }
+}
+
+/// A `--period` of whole days around today
+#[derive(Clone, Copy, ValueEnum)]
+enum Period {
+    Today,
+    /// The ISO week, starting on Monday
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// Gets the first and last days of the period containing `today`
+    fn days(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Period::Today => (today, today),
+            Period::Week => {
+                let week = today.week(Weekday::Mon);
+                (week.first_day(), week.last_day())
+            }
+            Period::Month => {
+                let first = today.with_day(1).expect("Every month has a first day");
+                (first, first + Months::new(1) - Days::new(1))
+            }
+            Period::Year => {
+                let first = today.with_ordinal(1).expect("Every year has a first day");
+                (first, first + Months::new(12) - Days::new(1))
+            }
+        }
+    }
+}
+
+/// A `--from` or `--to` bound, which is either a whole day or an exact time
+#[derive(Clone, Copy)]
+enum DateBound {
+    Day(NaiveDate),
+    Time(SystemTime),
+}
+
+#[derive(Args)]
+struct TagsArgs {
+    /// Only list tag names exactly as stored, one per line, without counts or alias folding
+    #[arg(long)]
+    raw: bool,
+}
+
+#[derive(Args)]
+struct TasksArgs {
+    /// Project to list descriptions for. Supports `*` and `?` wildcards, e.g. "Client/*"
+    project: String,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// File to import entries from
+    file: PathBuf,
+
+    /// Format the file was exported in
+    #[arg(long, value_enum, default_value_t = ImportFormat::JsonLines)]
+    format: ImportFormat,
+
+    /// What to do with an imported entry whose id is already in use
+    #[arg(long, value_enum, default_value_t = Conflict::Skip)]
+    on_conflict: Conflict,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+    /// A JSON array, as written by `export --format json`
+    Json,
+    /// One JSON object per line, as written by `export --format json-lines`
+    JsonLines,
+    /// A CSV file exported from Toggl Track. Every row becomes a new entry, whatever --on-conflict
+    /// says.
+    Toggl,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Conflict {
+    /// Keep the existing entry unchanged
+    Skip,
+    /// Replace the existing entry with the imported one
+    Overwrite,
+    /// Keep the existing entry and its times, adding any tags only the imported one has
+    MergeTags,
+}
+
+impl From<Conflict> for OnConflict {
+    fn from(conflict: Conflict) -> Self {
+        match conflict {
+            Conflict::Skip => OnConflict::Skip,
+            Conflict::Overwrite => OnConflict::Overwrite,
+            Conflict::MergeTags => OnConflict::MergeTags,
+        }
+    }
+}
+
+#[derive(Args)]
+struct CollapseArgs {
+    /// Local day to collapse, e.g. 2024-01-15
+    #[arg(long)]
+    date: NaiveDate,
+
+    /// Keep one summary entry per project, instead of one for the whole day
+    #[arg(long)]
+    per_project: bool,
+}
+
+#[derive(Args)]
+struct ArchiveArgs {
+    /// Move entries which ended before this, e.g. "2023-01-01" or "2023-01-01 09:00"
+    #[arg(long, value_parser = parse_date_bound)]
+    before: DateBound,
+
+    /// Archive file to move entries into, which is created if needed. Defaults to the data file's
+    /// name with ".archive.ron" as its extension.
+    #[arg(long)]
+    to: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Csv)]
+    format: ExportFormat,
+
+    /// Omit the column header row from CSV output
+    #[arg(long)]
+    no_headers: bool,
+
+    /// Only export entries from today
+    #[arg(long)]
+    today: bool,
+
+    /// What each row of a pivot export represents
+    #[arg(long, value_enum, default_value_t = Axis::Day)]
+    rows: Axis,
+
+    /// What each column of a pivot export represents
+    #[arg(long, value_enum, default_value_t = Axis::Project)]
+    cols: Axis,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    /// Longest gap (in minutes) between entries that still counts as continuous focus
+    #[arg(long, default_value_t = 5)]
+    max_gap: u64,
+
+    /// Average over every day since the first entry, not just days with entries
+    #[arg(long)]
+    calendar: bool,
+}
+
+#[derive(Args)]
+struct GapsArgs {
+    /// Local day to look for gaps in, instead of today, e.g. 2024-01-15
+    #[arg(long)]
+    date: Option<NaiveDate>,
+
+    /// Local time to start looking from, e.g. "09:00". Defaults to the start of the configured
+    /// work hours, then 09:00.
+    #[arg(long)]
+    from: Option<NaiveTime>,
+
+    /// Local time to look until, e.g. "17:00". Defaults to the end of the configured work hours,
+    /// then 17:00.
+    #[arg(long)]
+    to: Option<NaiveTime>,
+
+    /// Only list gaps longer than this many minutes
+    #[arg(long, default_value_t = 5)]
+    min_gap: u64,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// How to show the report. Markdown lists each day's entries as a table, ignoring the grouping,
+    /// ranking and rounding options
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+
+    /// What to group time by
+    #[arg(long, value_enum, default_value_t = ReportGrouping::Project)]
+    by: ReportGrouping,
+
+    /// Only show the N groups with the most time
+    #[arg(long)]
+    top: Option<usize>,
+
+    /// Leave out entries whose times are only estimated
+    #[arg(long)]
+    exclude_estimated: bool,
+
+    /// Leave out entries which aren't billable
+    #[arg(long)]
+    billable_only: bool,
+
+    /// Count the running entry's time so far, which is otherwise left out
+    #[arg(long)]
+    live: bool,
+
+    /// Round times to a multiple of this, e.g. "15m", "1h" or "90s" (a bare number is minutes)
+    #[arg(long, value_parser = parse_increment)]
+    round: Option<Duration>,
+
+    /// Which way to round with `--round`
+    #[arg(long, value_enum, default_value_t = Rounding::Up, requires = "round")]
+    round_mode: Rounding,
+
+    /// Round each entry before adding them up, instead of rounding each total
+    #[arg(long, requires = "round")]
+    round_each: bool,
+
+    /// Show earnings at this hourly rate, for projects without their own rate in the config
+    #[arg(long)]
+    rate: Option<f64>,
+
+    #[command(flatten)]
+    range: RangeArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// Projects (or categories) ranked by time
+    Text,
+    /// A table of entries per day, for pasting into notes
+    #[value(name = "md", alias = "markdown")]
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Rounding {
+    /// Round up to the next multiple
+    Up,
+    /// Round down to the previous multiple
+    Down,
+    /// Round to the closest multiple, rounding up when exactly halfway
+    Nearest,
+}
+
+impl From<Rounding> for RoundMode {
+    fn from(rounding: Rounding) -> Self {
+        match rounding {
+            Rounding::Up => RoundMode::Up,
+            Rounding::Down => RoundMode::Down,
+            Rounding::Nearest => RoundMode::Nearest,
+        }
+    }
+}
+
+#[derive(Args)]
+struct SummaryArgs {
+    /// Count the running entry's time so far, which is otherwise left out
+    #[arg(long)]
+    live: bool,
+
+    #[command(flatten)]
+    range: RangeArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportGrouping {
+    Project,
+    Category,
+    /// ISO weeks, starting on Monday, listed in order rather than ranked
+    Week,
+    /// Entries count fully towards each of their tags, so the totals can add up to more than the
+    /// time tracked. Entries without tags are left out.
+    Tag,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    #[value(name = "md", alias = "markdown")]
+    Markdown,
+    /// CSV table of hours, summed by `--rows` and `--cols`
+    Pivot,
+    /// A JSON array of every entry
+    Json,
+    /// One JSON object per entry, per line
+    JsonLines,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Axis {
+    Day,
+    Week,
+    Project,
+    Tag,
+}
+
+impl From<Axis> for PivotAxis {
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::Day => PivotAxis::Day,
+            Axis::Week => PivotAxis::Week,
+            Axis::Project => PivotAxis::Project,
+            Axis::Tag => PivotAxis::Tag,
+        }
+    }
+}
+
+impl Commands {
+    /// Checks whether this command only queries entries, so the data file shouldn't be locked or rewritten
+    fn is_read_only(&self) -> bool {
+        match self {
+            #[cfg(feature = "watch")]
+            Commands::Status(status_args) if status_args.watch => false,
+            Commands::Status(_)
+            | Commands::Log(_)
+            | Commands::Export(_)
+            | Commands::Stats(_)
+            | Commands::Report(_)
+            | Commands::Summary(_)
+            | Commands::Tags(_)
+            | Commands::Projects
+            | Commands::Search { .. }
+            | Commands::Tasks(_)
+            | Commands::Profiles
+            | Commands::Verify { .. }
+            | Commands::Gaps(_)
+            | Commands::Check
+            | Commands::Completions { .. }
+            | Commands::Heartbeat => true,
+            Commands::Start(_)
+            | Commands::Track(_)
+            | Commands::Stop(_)
+            | Commands::Continue
+            | Commands::Reopen { .. }
+            | Commands::Pause
+            | Commands::Resume
+            | Commands::Clear
+            | Commands::Undo
+            | Commands::Add(_)
+            | Commands::Edit(_)
+            | Commands::Tag(_)
+            | Commands::Delete(_)
+            | Commands::Split { .. }
+            | Commands::Merge { .. }
+            | Commands::Import(_)
+            | Commands::Collapse(_)
+            | Commands::Archive(_) => false,
+        }
+    }
 }
 
 impl Display for EntryInfo {
@@ -50,52 +848,1456 @@ impl Display for EntryInfo {
     }
 }
 
-fn main() -> atomichron::Result<()> {
+/// Colors projects are shown in, picked from by [`project_color`]
+const PROJECT_COLORS: [AnsiColors; 6] = [
+    AnsiColors::Blue,
+    AnsiColors::Magenta,
+    AnsiColors::Cyan,
+    AnsiColors::Yellow,
+    AnsiColors::BrightBlue,
+    AnsiColors::BrightMagenta,
+];
+
+/// Picks a color for a project from its name, so it's shown in the same color every time
+fn project_color(project: &str) -> AnsiColors {
+    let hash = project.bytes().fold(0usize, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte.into())
+    });
+    PROJECT_COLORS[hash % PROJECT_COLORS.len()]
+}
+
+/// Shows an entry like its `Display` impl does, but with its project colored and its tags dimmed
+/// when stdout supports color
+struct Styled<'a>(&'a Entry);
+
+impl Display for Styled<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entry = self.0;
+        if let Some(category) = entry.category() {
+            write!(f, "[{}] ", category)?;
+        }
+        let project = entry.project().as_deref().unwrap_or_default();
+        let tags = format!("{:?}", entry.tags());
+        write!(
+            f,
+            "{}: {} {}",
+            project.if_supports_color(Stdout, |text| text.color(project_color(project))),
+            entry.description().as_deref().unwrap_or_default(),
+            tags.if_supports_color(Stdout, |text| text.dimmed())
+        )
+    }
+}
+
+/// Formats a duration like [`format_duration`], in bold when stdout supports color
+fn styled_duration(duration: Duration) -> String {
+    let text = format_duration(duration);
+    text.if_supports_color(Stdout, |text| text.bold())
+        .to_string()
+}
+
+/// Describes an entry's timer as "Running" or "Paused", for status lines
+fn timer_state(entry: &Entry) -> &'static str {
+    if entry.is_paused() {
+        "Paused"
+    } else {
+        "Running"
+    }
+}
+
+/// An entry as shown in `--json` output
+#[derive(Serialize)]
+struct EntryView<'a> {
+    id: String,
+    category: Option<&'a str>,
+    project: Option<&'a str>,
+    description: Option<&'a str>,
+    tags: &'a [String],
+    /// RFC 3339, in local time
+    start_time: String,
+    end_time: Option<String>,
+    /// Not counting pauses, and up to now for a running entry
+    duration_seconds: u64,
+    running: bool,
+    paused: bool,
+    estimated: bool,
+    billable: bool,
+}
+
+impl<'a> From<&'a Entry> for EntryView<'a> {
+    fn from(entry: &'a Entry) -> Self {
+        let rfc3339 = |time| DateTime::<Local>::from(time).to_rfc3339();
+        EntryView {
+            id: entry.uuid().to_string(),
+            category: entry.category().as_deref(),
+            project: entry.project().as_deref(),
+            description: entry.description().as_deref(),
+            tags: entry.tags(),
+            start_time: rfc3339(entry.start_time()),
+            end_time: entry.end_time().map(rfc3339),
+            duration_seconds: entry.elapsed().as_secs(),
+            running: entry.end_time().is_none(),
+            paused: entry.is_paused(),
+            estimated: entry.estimated(),
+            billable: entry.billable(),
+        }
+    }
+}
+
+/// A row of `report` as shown in `--json` output
+#[derive(Serialize)]
+struct ReportRow<'a> {
+    name: &'a str,
+    seconds: u64,
+    percentage: f64,
+    estimated_seconds: u64,
+    running_seconds: u64,
+    earned: Option<f64>,
+}
+
+/// Prints a value as a single line of JSON
+fn print_json(value: &impl Serialize) -> atomichron::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+/// Runs a slow operation, showing a spinner with the given message if it takes longer than
+/// [`PROGRESS_THRESHOLD`]
+///
+/// Nothing is shown if stderr isn't a terminal, so piped output and scripts are unaffected.
+#[cfg(feature = "progress")]
+fn with_progress<T>(message: &'static str, operation: impl FnOnce() -> T) -> T {
+    use indicatif::ProgressBar;
+    use std::{
+        sync::mpsc::{self, RecvTimeoutError},
+        thread,
+    };
+
+    if !io::stderr().is_terminal() {
+        return operation();
+    }
+
+    let (done, finished) = mpsc::channel::<()>();
+    let spinner = thread::spawn(move || {
+        if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(PROGRESS_THRESHOLD) {
+            let spinner = ProgressBar::new_spinner().with_message(message);
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            // Dropping `done` disconnects the channel once the operation is over
+            let _ = finished.recv();
+            spinner.finish_and_clear();
+        }
+    });
+
+    let result = operation();
+    drop(done);
+    spinner.join().expect("Spinner thread doesn't panic");
+    result
+}
+
+/// Runs a slow operation. Without the `progress` feature, no spinner is shown.
+#[cfg(not(feature = "progress"))]
+fn with_progress<T>(_message: &'static str, operation: impl FnOnce() -> T) -> T {
+    operation()
+}
+
+/// Parses a local date and time from the command line, e.g. `2024-01-15 09:00`
+fn parse_local_datetime(text: &str) -> Result<SystemTime, String> {
+    let naive = ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .ok_or_else(|| {
+            format!(
+                "expected a local date and time like \"2024-01-15 09:00\", got \"{}\"",
+                text
+            )
+        })?;
+
+    naive
+        .and_local_timezone(Local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(|| format!("{} doesn't exist in the local timezone", text))
+}
+
+/// Parses a `--from` or `--to` bound: "today", "yesterday", a date like `2024-01-15`, or a date and
+/// time like `2024-01-15 09:00`
+fn parse_date_bound(text: &str) -> Result<DateBound, String> {
+    let today = Local::now().date_naive();
+    match text {
+        "today" => return Ok(DateBound::Day(today)),
+        "yesterday" => return Ok(DateBound::Day(today - Days::new(1))),
+        _ => {}
+    }
+
+    if let Ok(day) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(DateBound::Day(day));
+    }
+    parse_local_datetime(text).map(DateBound::Time).map_err(|_| {
+        format!(
+            "expected \"today\", \"yesterday\", a date like \"2024-01-15\" or a date and time like \"2024-01-15 09:00\", got \"{}\"",
+            text
+        )
+    })
+}
+
+/// Asks the user a yes or no question, defaulting to no
+///
+/// Always answers no without asking if stdin isn't a terminal, so scripts never hang. The question
+/// goes to stderr, so it doesn't end up in output piped elsewhere.
+fn confirm(question: &str) -> io::Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Shows the current status, refreshing every second, until the user quits
+///
+/// Reads single keystrokes in raw mode: `s` stops the running entry, `p` pauses or resumes it, and
+/// `q` (or Ctrl-C) quits. While an entry is running, a heartbeat is recorded every
+/// [`HEARTBEAT_INTERVAL`].
+#[cfg(feature = "watch")]
+fn watch_status(entries: &mut EntryList, data_file: &std::path::Path) -> atomichron::Result<()> {
+    use crossterm::{
+        cursor,
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{self, ClearType},
+    };
+    use std::time::Instant;
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+
+    let mut last_heartbeat: Option<Instant> = None;
+    let mut run = || -> atomichron::Result<()> {
+        loop {
+            if entries.current_entry()?.is_some()
+                && last_heartbeat.is_none_or(|time| time.elapsed() >= HEARTBEAT_INTERVAL)
+            {
+                record_heartbeat(data_file)?;
+                last_heartbeat = Some(Instant::now());
+            }
+
+            let status = match entries.current_entry()? {
+                Some(entry) => {
+                    let elapsed = entry.elapsed().as_secs();
+                    format!(
+                        "{} timer for {} ({}:{:02}:{:02})",
+                        timer_state(entry),
+                        entry,
+                        elapsed / 3600,
+                        elapsed / 60 % 60,
+                        elapsed % 60
+                    )
+                }
+                None => "No entry started".to_string(),
+            };
+            execute!(
+                stdout,
+                cursor::MoveToColumn(0),
+                terminal::Clear(ClearType::CurrentLine)
+            )?;
+            write!(stdout, "{}  [p]ause/resume [s]top [q]uit", status)?;
+            stdout.flush()?;
+
+            if !event::poll(Duration::from_secs(1))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Char('p') => match entries.current_entry()? {
+                        Some(entry) if entry.is_paused() => {
+                            entries.resume_current_entry()?;
+                        }
+                        Some(_) => {
+                            entries.pause_current_entry()?;
+                        }
+                        None => {}
+                    },
+                    KeyCode::Char('s') => {
+                        entries.stop_current_entry(None, None, Vec::new(), None)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    };
+    let result = run();
+
+    terminal::disable_raw_mode()?;
+    println!();
+    result
+}
+
+/// How often watch mode records a heartbeat for the running entry
+#[cfg(feature = "watch")]
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long loading or saving can take before a spinner is shown
+#[cfg(feature = "progress")]
+const PROGRESS_THRESHOLD: Duration = Duration::from_millis(300);
+
+/// Exit code for a command that succeeded but had nothing to act on, e.g. `stop` with no entry running
+const EXIT_NOTHING_TO_DO: u8 = 2;
+
+/// Exit code for command line arguments that couldn't be parsed
+const EXIT_USAGE: u8 = 64;
+
+/// Description of the exit codes, shown at the end of `--help`
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0   Success
+  1   Error, e.g. the data file couldn't be read or written
+  2   Nothing to do, e.g. `stop` with no entry running
+  64  Invalid arguments";
+
+/// Name of the data file used when no file or profile is selected
+const DEFAULT_DATA_FILE: &str = "entries.ron";
+
+/// Environment variable naming the data file, if neither `--file` nor a profile is given
+const DATA_FILE_VAR: &str = "ATOMICHRON_FILE";
+
+/// Name of the config file looked for when `--config` isn't given
+const DEFAULT_CONFIG_FILE: &str = "config.ron";
+
+/// Loads the config from `--config`, which must exist, or else from the platform's config
+/// directory. A config file in the working directory is still read if there's none there.
+fn load_config(args: &Cli) -> atomichron::Result<Config> {
+    if let Some(file) = &args.config {
+        return Config::load(file);
+    }
+
+    match ProjectDirs::from("", "", "atomichron") {
+        Some(dirs) if dirs.config_dir().join(DEFAULT_CONFIG_FILE).exists() => {
+            Config::load(dirs.config_dir().join(DEFAULT_CONFIG_FILE))
+        }
+        _ => Config::load_or_default(DEFAULT_CONFIG_FILE),
+    }
+}
+
+/// Works out which data file to use, from `--file`, then the profile, then [`DATA_FILE_VAR`], then
+/// the platform's data directory (or the working directory, if there isn't one)
+fn resolve_data_file(args: &Cli, config: &Config) -> atomichron::Result<PathBuf> {
+    if let Some(file) = &args.data_file {
+        return Ok(file.clone());
+    }
+    if let Some(profile) = &args.profile {
+        return Ok(config.profile_path(profile)?.to_path_buf());
+    }
+    if let Some(file) = env::var_os(DATA_FILE_VAR) {
+        return Ok(file.into());
+    }
+
+    Ok(match ProjectDirs::from("", "", "atomichron") {
+        Some(dirs) => dirs.data_dir().join(DEFAULT_DATA_FILE),
+        None => PathBuf::from(DEFAULT_DATA_FILE),
+    })
+}
+
+fn main() -> atomichron::Result<ExitCode> {
+    // Read args and config
+    let args = match Cli::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            // Clap would exit with 2 here, which is taken to mean there was nothing to do
+            e.print()?;
+            return Ok(if e.use_stderr() {
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::SUCCESS
+            });
+        }
+    };
+    if args.no_color {
+        owo_colors::set_override(false);
+    }
+    // Completions are generated from the arguments alone, without touching any files
+    if let Commands::Completions { shell } = args.command {
+        let name = env!("CARGO_PKG_NAME");
+        clap_complete::generate(shell, &mut Cli::command(), name, &mut io::stdout());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let config = load_config(&args)?;
+
     // Load entries
-    let mut entries = EntryList::load_or_create("./entries.ron")?;
+    let data_file = resolve_data_file(&args, &config)?;
+    let data_file = data_file.as_path();
+    // Verifying doesn't load the entries, so it still works when they fail their checksum
+    if let Commands::Verify { accept } = args.command {
+        return verify_data_file(data_file, accept, args.wait, config.pretty_data_file);
+    }
+    let read_only = args.command.is_read_only();
+    if !read_only {
+        if let Some(parent) = data_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    // Held until after saving, so concurrent commands can't overwrite each other's changes
+    let mut lock = if read_only {
+        None
+    } else {
+        Some(DataFileLock::acquire(data_file, args.wait)?)
+    };
+    let mut entries = with_progress("Loading entries...", || {
+        if read_only {
+            EntryList::load_readonly(data_file)
+        } else {
+            EntryList::load_or_create(data_file)
+        }
+    })
+    .inspect_err(|e| {
+        if matches!(e, Error::IntegrityCheckFailed) {
+            eprintln!("The data file doesn't match its checksum, see `atomichron verify`");
+        }
+    })?;
+    apply_config(&mut entries, &config);
 
-    // Read and process args
-    let args = Cli::parse();
+    // Times are only converted for display, never when stored
+    let zone = match args.tz.as_ref().or(config.timezone.as_ref()) {
+        Some(name) => name.parse()?,
+        None => DisplayZone::Local,
+    };
+    let time_format = args
+        .time_format
+        .as_deref()
+        .or(config.time_format.as_deref())
+        .unwrap_or(DEFAULT_TIME_FORMAT);
+    check_time_format(time_format)?;
+    let show_time = |time| zone.format(time, time_format);
 
+    // `check` lists problems itself
+    if !matches!(args.command, Commands::Check) {
+        for problem in entries.validate() {
+            eprintln!("Warning: {}", problem);
+        }
+    }
+    if args.strict_validation {
+        entries.validate_strict()?;
+    }
+
+    // Process args
+
+    let mut exit_code = ExitCode::SUCCESS;
     match &args.command {
-        Commands::Start(info) => {
-            if let Some(entry) = entries.stop_current_entry(None, None, Vec::new()) {
+        Commands::Start(start_args) => {
+            let info = &start_args.info;
+            // Defaults from the config only fill in what wasn't given on the command line
+            let project = info.project.clone().or(config.default_project.clone());
+            let tags = if info.tags.is_empty() {
+                config.default_tags.clone()
+            } else {
+                info.tags.clone()
+            };
+            let outcome = match start_args.at {
+                Some(start) => {
+                    entries.set_allow_future_starts(start_args.allow_future);
+                    entries.start_entry_at(
+                        start,
+                        project,
+                        info.description.clone(),
+                        tags,
+                        info.category.clone(),
+                        !start_args.no_stop,
+                    )?
+                }
+                None => entries.start_entry(
+                    project,
+                    info.description.clone(),
+                    tags,
+                    info.category.clone(),
+                    !start_args.no_stop,
+                )?,
+            };
+            if let Some(entry) = outcome.stopped.as_ref().filter(|_| !args.json) {
+                println!("Stopping entry {}", entry);
+            }
+            if start_args.estimated {
+                entries.set_estimated(outcome.started, true)?;
+            }
+            if let Some(billable) = info.billable.value() {
+                entries.set_billable(outcome.started, billable)?;
+            }
+
+            let new_entry = entries
+                .get_entry(outcome.started)
+                .expect("An entry was just started");
+
+            if let Some(work_hours) = &config.work_hours {
+                if entries.violates_work_hours(new_entry, work_hours) {
+                    if start_args.strict {
+                        // Bail before saving, so nothing is actually started (or stopped)
+                        return Err(Error::OutsideWorkHours);
+                    }
+                    eprintln!("Warning: this entry falls outside the configured work hours");
+                }
+            }
+            if args.json {
+                print_json(&json!({
+                    "stopped": outcome.stopped.as_ref().map(EntryView::from),
+                    "started": EntryView::from(new_entry),
+                }))?;
+            } else {
+                println!("Starting entry {}", new_entry);
+            }
+            clear_heartbeats(data_file)?;
+        }
+        Commands::Track(track_args) => {
+            let description = track_args
+                .description
+                .clone()
+                .unwrap_or_else(|| track_args.command.join(" "));
+            let outcome = entries.start_entry(
+                track_args.project.clone(),
+                Some(description),
+                track_args.tags.clone(),
+                track_args.category.clone(),
+                true,
+            )?;
+            if let Some(entry) = &outcome.stopped {
                 println!("Stopping entry {}", entry);
             }
 
-            let new_entry = entries.start_entry(
+            // Ctrl-C still reaches the command, but shouldn't stop us before the entry is stopped.
+            // If the handler can't be installed, Ctrl-C just leaves the entry running as before.
+            let _ = ctrlc::set_handler(|| {});
+            // Spawned before saving, so nothing is started if the command can't be run
+            let mut child = process::Command::new(&track_args.command[0])
+                .args(&track_args.command[1..])
+                .spawn()?;
+            println!(
+                "Starting entry {}",
+                entries
+                    .get_entry(outcome.started)
+                    .expect("An entry was just started")
+            );
+            clear_heartbeats(data_file)?;
+
+            // Other commands can change entries while the command runs, so save and unlock until
+            // it's finished, then pick up their changes
+            save_entries(&entries, data_file, config.pretty_data_file)?;
+            drop(lock.take());
+            // Even if waiting fails, the command has stopped being tracked, so the entry is stopped
+            // and saved before the error is passed on
+            let status = child.wait();
+            lock = Some(DataFileLock::acquire(data_file, true)?);
+            entries = EntryList::load_or_create(data_file)?;
+            apply_config(&mut entries, &config);
+
+            if entries.current_entry_id() == Some(outcome.started) {
+                if let Some((entry, _)) =
+                    entries.stop_current_entry(None, None, Vec::new(), None)?
+                {
+                    println!("Stopping entry {}", entry);
+                }
+            } else {
+                eprintln!("Warning: the entry was stopped while the command ran");
+            }
+            let status = match status {
+                Ok(status) => status,
+                Err(e) => {
+                    save_entries(&entries, data_file, config.pretty_data_file)?;
+                    return Err(e.into());
+                }
+            };
+            // A command killed by a signal has no exit code of its own
+            exit_code = match status.code() {
+                Some(code) => ExitCode::from(code as u8),
+                None => ExitCode::FAILURE,
+            };
+        }
+        Commands::Stop(info) => {
+            let heartbeats = load_heartbeats(data_file)?;
+            let mut end_time = SystemTime::now();
+            if let Some(gap) = entries
+                .current_entry()?
+                .and_then(|entry| entry.suspected_sleep_gap(&heartbeats))
+            {
+                eprintln!(
+                    "Warning: this entry hasn't had a heartbeat for {}, the machine may have been asleep",
+                    format_hours_minutes(gap)
+                );
+                // JSON output is for scripts, so the entry is left untrimmed rather than asking
+                if !args.json && confirm("Trim the entry to end at its last heartbeat?")? {
+                    end_time -= gap;
+                }
+            }
+
+            let current = entries.current_entry_id();
+            if let (Some(billable), Some(id)) = (info.billable.value(), current) {
+                entries.edit_entry(id, None, None, None, None, Some(billable))?;
+            }
+            match entries.stop_current_entry_at(
+                end_time,
                 info.project.clone(),
                 info.description.clone(),
                 info.tags.clone(),
-            );
-            println!("Starting entry {}", new_entry);
-        }
-        Commands::Stop(info) => match entries.stop_current_entry(
-            info.project.clone(),
-            info.description.clone(),
-            info.tags.clone(),
-        ) {
-            Some(entry) => println!("Stopping entry {}", entry),
-            None => println!("No entry started"),
+                info.category.clone(),
+            )? {
+                Some((entry, outcome)) if args.json => print_json(&json!({
+                    "stopped": EntryView::from(entry),
+                    "already_stopped": outcome == StopOutcome::AlreadyStopped,
+                }))?,
+                None if args.json => {
+                    print_json(&json!({ "stopped": null }))?;
+                    exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                }
+                Some((entry, StopOutcome::Stopped)) => println!("Stopping entry {}", entry),
+                Some((entry, StopOutcome::AlreadyStopped)) => eprintln!(
+                    "Warning: the current entry {} had already been stopped, its end time was left unchanged",
+                    entry
+                ),
+                None => {
+                    println!("No entry started");
+                    exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                }
+            }
+            clear_heartbeats(data_file)?;
+        }
+        Commands::Clear => {
+            match entries.clear_current_entry()? {
+                Some(entry) => println!("Clearing entry {}", entry),
+                None => {
+                    println!("No entry started");
+                    exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                }
+            }
+            clear_heartbeats(data_file)?;
+        }
+        Commands::Undo => match entries.undo_remove() {
+            Some(entry) => println!("Restored entry {}", entry),
+            None => {
+                println!("Nothing to undo");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
         },
-        Commands::Clear => match entries.clear_current_entry() {
-            Some(entry) => println!("Clearing entry {}", entry),
-            None => println!("No entry started"),
+        Commands::Reopen { id } => {
+            let uuid = match id {
+                Some(id) => Some(entries.find_by_prefix(id)?.uuid()),
+                None => entries.last_completed_n(1).pop().map(Entry::uuid),
+            };
+            match uuid {
+                Some(uuid) => println!("Reopened entry {}", entries.reopen_entry(uuid)?),
+                None => {
+                    println!("No finished entry to reopen — run `atomichron start` to begin.");
+                    exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                }
+            }
+        }
+        Commands::Continue => match entries.resume_last()? {
+            Some(outcome) => {
+                if let Some(entry) = outcome.stopped {
+                    println!("Stopping entry {}", entry);
+                }
+                let started = entries
+                    .get_entry(outcome.started)
+                    .expect("An entry was just started");
+                println!("Continuing entry {}", started);
+                clear_heartbeats(data_file)?;
+            }
+            None => {
+                println!("No finished entry to continue — run `atomichron start` to begin.");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+        },
+        Commands::Pause => match entries.pause_current_entry() {
+            Ok(Some(entry)) => println!("Pausing entry {}", entry),
+            Ok(None) => {
+                println!("No entry started");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+            Err(Error::AlreadyPaused) => {
+                println!("Entry is already paused");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+            Err(e) => return Err(e),
         },
-        Commands::Status => match entries.current_entry() {
-            Some(entry) => println!("Running timer for {}", entry),
-            None => println!("No entry started"),
+        Commands::Resume => match entries.resume_current_entry() {
+            Ok(Some(entry)) => println!("Resuming entry {}", entry),
+            Ok(None) => {
+                println!("No entry started");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+            Err(Error::NotPaused) => {
+                println!("Entry isn't paused");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+            Err(e) => return Err(e),
         },
-        Commands::Log => {
-            let list = entries.get_entries_in_order(false);
-            for entry in list {
-                println!("{}", entry);
+        #[cfg(feature = "watch")]
+        Commands::Status(status_args) if status_args.watch => {
+            watch_status(&mut entries, data_file)?
+        }
+        Commands::Status(status_args)
+            if args.json || matches!(status_args.format, StatusFormat::Json) =>
+        {
+            let status = entries.status();
+            let running = status.running.as_ref();
+            let json = serde_json::json!({
+                "running": running.is_some_and(|entry| !entry.is_paused()),
+                "paused": running.is_some_and(Entry::is_paused),
+                "id": running.map(|entry| entry.uuid().to_string()),
+                "project": running.and_then(|entry| entry.project().as_deref()),
+                "description": running.and_then(|entry| entry.description().as_deref()),
+                "tags": running.map(Entry::tags),
+                "category": running.and_then(|entry| entry.category().as_deref()),
+                "elapsed_seconds": status.elapsed.as_secs(),
+                "today_seconds": status.today.as_secs(),
+            });
+            println!("{}", json);
+        }
+        Commands::Status(status_args) => {
+            let status = entries.status();
+            match &status.running {
+                Some(entry) => {
+                    let color = if entry.is_paused() {
+                        AnsiColors::Yellow
+                    } else {
+                        AnsiColors::Green
+                    };
+                    println!(
+                        "{} timer {} for {} ({})",
+                        timer_state(entry).if_supports_color(Stdout, |text| text.color(color)),
+                        entry.short_id(),
+                        Styled(entry),
+                        styled_duration(status.elapsed)
+                    )
+                }
+                None => println!("No entry started"),
+            }
+            println!("Today: {}", format_hours_minutes(status.today));
+            if let Some(threshold) = status_args.warn_after.or(config.long_running_warning) {
+                if entries.running_exceeds(threshold) {
+                    println!(
+                        "WARNING: this timer has been running for over {}, did you forget to stop it?",
+                        format_hours_minutes(threshold)
+                    );
+                }
+            }
+
+            let recent = entries.last_completed_n(status_args.recent);
+            if !recent.is_empty() {
+                println!("\nRecently completed:");
+            }
+            for entry in recent {
+                println!("  {} ({})", Styled(entry), styled_duration(entry.elapsed()));
+            }
+        }
+        Commands::Log(log_args) => {
+            let (since, until) = log_args.range.range();
+            let mut list = entries.get_entries_in_range(since, until, false);
+            if log_args.running_first {
+                // The sort is stable, so everything else stays newest first
+                list.sort_by_key(|entry| entry.end_time().is_some());
+            }
+            if let Some(pattern) = &log_args.project {
+                list.retain(|entry| entry.project_matches(pattern));
+            }
+            if log_args.no_project {
+                list.retain(|entry| entry.project().is_none());
+            }
+            if let Some(tag) = &log_args.tag {
+                list.retain(|entry| entry.tags().contains(tag));
+            }
+            let minutes = |minutes: u64| Duration::from_secs(minutes * 60);
+            let min = log_args.min_duration.map(minutes);
+            let max = log_args.max_duration.map(minutes);
+            list.retain(|entry| entry.duration_within(min, max));
+            let matched = list.len();
+            let list = page(&list, log_args.offset, log_args.limit);
+
+            if args.json {
+                print_json(&json!({
+                    "entries": list.iter().copied().map(EntryView::from).collect::<Vec<_>>(),
+                    "count": list.len(),
+                    "total_seconds": total_of(&list, log_args.live).as_secs(),
+                }))?;
+            } else {
+                if entries.is_empty() {
+                    println!("No entries yet — run `atomichron start` to begin.");
+                } else if matched == 0 {
+                    println!("No entries match the selected filters.");
+                } else if list.is_empty() {
+                    println!("No entries left after --offset {}.", log_args.offset);
+                }
+                for entry in &list {
+                    let id = if log_args.long_ids {
+                        entry.uuid().to_string()
+                    } else {
+                        entry.short_id()
+                    };
+                    let start = show_time(entry.start_time());
+                    print!("{}  {}  {} ", id, start, Styled(entry));
+                    match entry.duration() {
+                        Some(duration) => println!("({})", styled_duration(duration)),
+                        None => println!("({}, running)", styled_duration(entry.elapsed())),
+                    }
+                }
+                if !list.is_empty() {
+                    let shown = match list.len() {
+                        count if count < matched => format!("{} of {}", count, matched),
+                        count => count.to_string(),
+                    };
+                    println!(
+                        "{} {}, total {}",
+                        shown,
+                        if matched == 1 { "entry" } else { "entries" },
+                        format_hours_minutes(total_of(&list, log_args.live))
+                    );
+                }
+            }
+        }
+        Commands::Export(export_args) => {
+            let range = export_args.today.then(|| day_range(Local::now().date_naive()));
+
+            match export_args.format {
+                ExportFormat::Csv => {
+                    entries.export_csv(io::stdout(), !export_args.no_headers, range)?
+                }
+                ExportFormat::Markdown => entries.export_markdown(io::stdout(), range)?,
+                ExportFormat::Json => entries.export_json(io::stdout(), range)?,
+                ExportFormat::JsonLines => entries.export_json_lines(io::stdout(), range)?,
+                ExportFormat::Pivot => print!(
+                    "{}",
+                    entries.export_pivot_csv(
+                        export_args.rows.into(),
+                        export_args.cols.into(),
+                        !export_args.no_headers,
+                        range
+                    )?
+                ),
+            }
+        }
+        Commands::Stats(stats_args) => {
+            let max_gap = Duration::from_secs(stats_args.max_gap * 60);
+            // Averages only count finished entries, so there's nothing to show without one
+            let finished = entries.iter().filter(|entry| entry.end_time().is_some());
+            let first = finished.min();
+            let focus = entries.longest_focus_block(max_gap);
+            if let (Some(first), Some((start, _, length))) = (first, focus) {
+                println!(
+                    "Longest focus: {} on {}",
+                    format_hours_minutes(length),
+                    zone.format(start, "%Y-%m-%d")
+                );
+
+                let (since, until) = (first.start_time(), SystemTime::now());
+                let basis = if stats_args.calendar {
+                    AverageBasis::Calendar
+                } else {
+                    AverageBasis::Active
+                };
+                println!(
+                    "Average per day: {}",
+                    format_hours_minutes(entries.average_per_day(since, until, basis))
+                );
+                println!(
+                    "Average per week: {}",
+                    format_hours_minutes(entries.average_per_week(since, until, basis))
+                );
+            } else {
+                println!("No finished entries to show stats for yet.");
+            }
+        }
+        Commands::Report(report_args) if matches!(report_args.format, ReportFormat::Markdown) => {
+            let (since, until) = report_args.range.range();
+            entries.set_billable_only(report_args.billable_only);
+            print!("{}", entries.report_markdown(since, until));
+        }
+        Commands::Report(report_args) => {
+            let (since, until) = report_args.range.range();
+            let include_estimated = !report_args.exclude_estimated;
+            entries.set_billable_only(report_args.billable_only);
+            let rounding = report_args
+                .round
+                .map(|increment| (increment, RoundMode::from(report_args.round_mode)));
+            let round_each = rounding.filter(|_| report_args.round_each);
+            let round_totals = rounding.filter(|_| !report_args.round_each);
+
+            let totals_for =
+                |entries: &EntryList, include_estimated| match (report_args.by, round_each) {
+                    (ReportGrouping::Project, None) => {
+                        entries.total_duration_by_project(since, until, include_estimated)
+                    }
+                    (ReportGrouping::Category, None) => {
+                        entries.total_duration_by_category(since, until, include_estimated)
+                    }
+                    (ReportGrouping::Project, Some((increment, mode))) => entries
+                        .rounded_total_duration_by_project(
+                            since,
+                            until,
+                            include_estimated,
+                            increment,
+                            mode,
+                        ),
+                    (ReportGrouping::Category, Some((increment, mode))) => entries
+                        .rounded_total_duration_by_category(
+                            since,
+                            until,
+                            include_estimated,
+                            increment,
+                            mode,
+                        ),
+                    (ReportGrouping::Week, None) => {
+                        entries.total_duration_by_week(since, until, include_estimated)
+                    }
+                    (ReportGrouping::Week, Some((increment, mode))) => entries
+                        .rounded_total_duration_by_week(
+                            since,
+                            until,
+                            include_estimated,
+                            increment,
+                            mode,
+                        ),
+                    (ReportGrouping::Tag, None) => {
+                        entries.total_duration_by_tag(since, until, include_estimated)
+                    }
+                    (ReportGrouping::Tag, Some((increment, mode))) => entries
+                        .rounded_total_duration_by_tag(
+                            since,
+                            until,
+                            include_estimated,
+                            increment,
+                            mode,
+                        ),
+                };
+            let (mut totals, mut exact_totals) = (
+                totals_for(&entries, include_estimated),
+                totals_for(&entries, false),
+            );
+
+            // Only billable time earns anything, whether or not other time is shown
+            let default_rate = report_args.rate.or(config.hourly_rate);
+            let show_earnings = default_rate.is_some() || !config.project_rates.is_empty();
+            entries.set_billable_only(true);
+            let mut billable_totals = totals_for(&entries, include_estimated);
+            entries.set_billable_only(report_args.billable_only);
+
+            // The running entry only falls in the range if it reaches up to now
+            let include_running = report_args.live && until >= SystemTime::now();
+            let running = entries
+                .current_entry()?
+                .filter(|entry| include_running && (include_estimated || !entry.estimated()))
+                .filter(|entry| entry.billable() || !report_args.billable_only)
+                .map(|entry| {
+                    let labels = match report_args.by {
+                        ReportGrouping::Project => vec![entries.project_label(entry).to_string()],
+                        ReportGrouping::Category => {
+                            vec![entries.category_label(entry).to_string()]
+                        }
+                        ReportGrouping::Week => {
+                            let (year, week) = entry.iso_week();
+                            vec![format_iso_week(year, week)]
+                        }
+                        ReportGrouping::Tag => entries.tag_labels(entry),
+                    };
+                    let elapsed = match round_each {
+                        Some((increment, mode)) => round_duration(entry.elapsed(), increment, mode),
+                        None => entry.elapsed(),
+                    };
+                    (labels, elapsed)
+                });
+            if let Some((labels, elapsed)) = &running {
+                let billable = entries.current_entry()?.is_some_and(Entry::billable);
+                for label in labels {
+                    *totals.entry(label.clone()).or_default() += *elapsed;
+                    if billable {
+                        *billable_totals.entry(label.clone()).or_default() += *elapsed;
+                    }
+                }
+            }
+            if let Some((increment, mode)) = round_totals {
+                let all_totals = totals
+                    .values_mut()
+                    .chain(exact_totals.values_mut())
+                    .chain(billable_totals.values_mut());
+                for total in all_totals {
+                    *total = round_duration(*total, increment, mode);
+                }
+            }
+            let earned = |name: &str| {
+                let billable = billable_totals.get(name).copied().unwrap_or_default();
+                config
+                    .rate_for(name, default_rate)
+                    .map(|rate| earnings(billable, rate))
+            };
+
+            let mut ranked = rank(totals);
+            if let ReportGrouping::Week = report_args.by {
+                // Week labels sort chronologically
+                ranked.sort_unstable_by(|(a, ..), (b, ..)| a.cmp(b));
+            }
+            if let Some(top) = report_args.top {
+                ranked.truncate(top);
+            }
+            // Splits a total into its estimated and running parts
+            let parts = |name: &str, duration: Duration| {
+                let live = match &running {
+                    Some((labels, elapsed)) if labels.iter().any(|label| label == name) => *elapsed,
+                    _ => Duration::ZERO,
+                };
+                // Rounding can leave the parts adding up to slightly more than the total
+                let exact = exact_totals.get(name).copied().unwrap_or_default();
+                (duration.saturating_sub(live).saturating_sub(exact), live)
+            };
+            // Groups left out by --top still count towards the total
+            let total_earnings = show_earnings.then(|| {
+                billable_totals
+                    .keys()
+                    .filter_map(|name| earned(name))
+                    .sum::<f64>()
+            });
+
+            if args.json {
+                let rows: Vec<_> = ranked
+                    .iter()
+                    .map(|(name, duration, percentage)| {
+                        let (estimated, live) = parts(name, *duration);
+                        ReportRow {
+                            name,
+                            seconds: duration.as_secs(),
+                            percentage: *percentage,
+                            estimated_seconds: estimated.as_secs(),
+                            running_seconds: live.as_secs(),
+                            earned: earned(name),
+                        }
+                    })
+                    .collect();
+                print_json(&json!({ "rows": rows, "total_earnings": total_earnings }))?;
+            } else if ranked.is_empty() {
+                println!("No data for the selected range.");
+            } else {
+                let width = ranked.iter().map(|(name, ..)| name.len()).max();
+                for (name, duration, percentage) in &ranked {
+                    print!(
+                        "{:width$}  {:>8}  {:5.1}%  {}",
+                        name,
+                        format_hours_minutes(*duration),
+                        percentage,
+                        "#".repeat((percentage / 5.0).round() as usize),
+                        width = width.unwrap_or_default(),
+                    );
+                    if let Some(amount) = earned(name) {
+                        print!(" (earned {:.2})", amount);
+                    }
+
+                    let (estimated, live) = parts(name, *duration);
+                    if !estimated.is_zero() {
+                        print!(" ({} estimated)", format_hours_minutes(estimated));
+                    }
+                    if !live.is_zero() {
+                        print!(" (+{} running)", format_hours_minutes(live));
+                    }
+                    println!();
+                }
+                if let Some(total) = total_earnings {
+                    println!("Total earnings: {:.2}", total);
+                }
+            }
+        }
+        Commands::Add(add_args) => {
+            let info = &add_args.info;
+            entries.set_allow_future_starts(add_args.allow_future);
+            let id = entries
+                .add_entry(
+                    add_args.from,
+                    add_args.to,
+                    info.project.clone(),
+                    info.description.clone(),
+                    info.tags.clone(),
+                    info.category.clone(),
+                )?
+                .uuid();
+            if let Some(billable) = info.billable.value() {
+                entries.set_billable(id, billable)?;
+            }
+            let entry = entries.set_estimated(id, add_args.estimated)?;
+            println!("Added entry {}", entry);
+        }
+        Commands::Edit(edit_args) => {
+            let uuid = entries.find_by_prefix(&edit_args.id)?.uuid();
+            let tags = if edit_args.clear_tags {
+                Some(Vec::new())
+            } else {
+                edit_args.tags.clone()
+            };
+
+            let entry = entries.edit_entry(
+                uuid,
+                edit_args.project.clone(),
+                edit_args.description.clone(),
+                tags,
+                edit_args.category.clone(),
+                edit_args.billable.value(),
+            )?;
+            println!("Updated entry {}", entry);
+        }
+        Commands::Tag(TagCommand::Add { id, tags }) => {
+            let uuid = entries.find_by_prefix(id)?.uuid();
+            let entry = entries.add_tags(uuid, tags.clone())?;
+            println!("Updated entry {}", entry);
+        }
+        Commands::Tag(TagCommand::Rm { id, tags }) => {
+            let uuid = entries.find_by_prefix(id)?.uuid();
+            let entry = entries.remove_tags(uuid, tags.clone())?;
+            println!("Updated entry {}", entry);
+        }
+        Commands::Delete(DeleteArgs { id: Some(id), .. }) => {
+            let uuid = entries.find_by_prefix(id)?.uuid();
+            let entry = entries.delete_entry(uuid).expect("Entry was just found");
+            println!("Deleted entry {}", entry);
+        }
+        Commands::Delete(delete_args) => {
+            let before = match delete_args.before {
+                Some(DateBound::Day(day)) => Some(day_range(day).0),
+                Some(DateBound::Time(time)) => Some(time),
+                None => None,
+            };
+            let matches = |entry: &Entry| {
+                delete_args
+                    .project
+                    .as_ref()
+                    .is_none_or(|pattern| entry.project_matches(pattern))
+                    && delete_args
+                        .tag
+                        .as_ref()
+                        .is_none_or(|tag| entry.tags().contains(tag))
+                    && before.is_none_or(|before| entry.start_time() < before)
+            };
+
+            let running = entries.current_entry()?.filter(|entry| matches(entry));
+            if let Some(entry) = running.filter(|_| !delete_args.force) {
+                println!("Skipping running entry {}, use --force to delete it", entry);
+            }
+            let current = entries.current_entry_id().filter(|_| !delete_args.force);
+            let deletable = |entry: &Entry| matches(entry) && Some(entry.uuid()) != current;
+            let count = entries.iter().filter(|entry| deletable(entry)).count();
+            // Bulk deletes can't be undone, so check first when there's someone to ask
+            let noun = if count == 1 { "entry" } else { "entries" };
+            let question = format!("Delete {} {}? This can't be undone.", count, noun);
+            if count > 0 && io::stdin().is_terminal() && !confirm(&question)? {
+                println!("Nothing deleted");
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            } else {
+                match entries.delete_matching(deletable).len() {
+                    0 => {
+                        println!("No entries to delete");
+                        exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                    }
+                    1 => println!("Deleted 1 entry"),
+                    count => println!("Deleted {} entries", count),
+                }
+            }
+        }
+        Commands::Split { id, at } => {
+            let uuid = entries.find_by_prefix(id)?.uuid();
+            let (first, second) = entries.split_entry(uuid, *at)?;
+            for half in [first, second] {
+                let entry = entries.get_entry(half).expect("Entry was just split");
+                println!("{}  {}", entry.short_id(), entry);
+            }
+        }
+        Commands::Merge { first, second } => {
+            let first = entries.find_by_prefix(first)?;
+            let second = entries.find_by_prefix(second)?;
+
+            let (earlier, later) = if first.start_time() <= second.start_time() {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            let gap = earlier
+                .end_time()
+                .and_then(|end| later.start_time().duration_since(end).ok())
+                .filter(|gap| !gap.is_zero());
+            if let Some(gap) = gap {
+                eprintln!(
+                    "Warning: the entries are {} apart, and that gap now counts towards the merged entry",
+                    format_hours_minutes(gap)
+                );
+            }
+
+            let (first, second) = (first.uuid(), second.uuid());
+            let merged = entries.merge_entries(first, second)?;
+            let entry = entries.get_entry(merged).expect("Entry was just merged");
+            println!("Merged into {}  {}", entry.short_id(), entry);
+        }
+        Commands::Import(import_args) => {
+            let file = BufReader::new(File::open(&import_args.file)?);
+            let on_conflict = import_args.on_conflict.into();
+            let summary = match import_args.format {
+                ImportFormat::Json => entries.import_json(file, on_conflict)?,
+                ImportFormat::JsonLines => entries.import_json_lines(file, on_conflict)?,
+                ImportFormat::Toggl => {
+                    let import = entries.import_toggl_csv(file)?;
+                    for (line, e) in &import.skipped {
+                        match e.source() {
+                            Some(source) => eprintln!("Skipped line {}: {}: {}", line, e, source),
+                            None => eprintln!("Skipped line {}: {}", line, e),
+                        }
+                    }
+                    ImportSummary {
+                        added: import.imported.len(),
+                        updated: 0,
+                        skipped: import.skipped.len(),
+                    }
+                }
+            };
+            println!(
+                "Imported entries: {} added, {} updated, {} skipped",
+                summary.added, summary.updated, summary.skipped
+            );
+        }
+        Commands::Collapse(collapse_args) => {
+            let removed = entries.collapse_day(collapse_args.date, collapse_args.per_project);
+            if removed.is_empty() {
+                println!("No entries to collapse on {}", collapse_args.date);
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            } else {
+                println!(
+                    "Collapsed {} entries on {}",
+                    removed.len(),
+                    collapse_args.date
+                );
+            }
+        }
+        Commands::Archive(archive_args) => {
+            let cutoff = match archive_args.before {
+                DateBound::Day(day) => day_range(day).0,
+                DateBound::Time(time) => time,
+            };
+            let archive_file = match &archive_args.to {
+                Some(path) => path.clone(),
+                None => archive_path(data_file),
+            };
+            match entries.archive_before(cutoff, &archive_file)? {
+                0 => {
+                    println!("No entries to archive");
+                    exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+                }
+                1 => println!("Archived 1 entry to {}", archive_file.display()),
+                count => println!("Archived {} entries to {}", count, archive_file.display()),
+            }
+        }
+        Commands::Summary(summary_args) => {
+            let (since, until) = summary_args.range.range();
+            let totals = entries.daily_totals(since, until, summary_args.live);
+            if args.json {
+                let days: Vec<_> = totals
+                    .iter()
+                    .map(|(day, total)| json!({ "date": day, "seconds": total.as_secs() }))
+                    .collect();
+                print_json(&json!({ "days": days }))?;
+            } else if totals.is_empty() {
+                println!("No entries to summarize");
+            } else {
+                for (day, total) in totals {
+                    println!(
+                        "{}  {}",
+                        day.format("%Y-%m-%d %a"),
+                        format_hours_minutes(total)
+                    );
+                }
+            }
+        }
+        Commands::Tags(tags_args) if tags_args.raw => {
+            for tag in entries.all_tags() {
+                println!("{}", tag);
+            }
+        }
+        Commands::Tags(_) => {
+            let histogram = entries.tag_histogram_with_aliases(&config.tag_aliases);
+            if histogram.is_empty() {
+                println!("No tags used yet.");
+            }
+            let width = histogram.keys().map(String::len).max();
+            for (tag, count) in histogram {
+                println!(
+                    "{:width$}  {}",
+                    tag,
+                    count,
+                    width = width.unwrap_or_default()
+                );
+            }
+        }
+        Commands::Projects => {
+            for project in entries.all_projects() {
+                println!("{}", project);
+            }
+        }
+        Commands::Tasks(tasks_args) => {
+            let descriptions = entries.descriptions_for_project(&tasks_args.project);
+            if descriptions.is_empty() {
+                println!(
+                    "No descriptions recorded for project {}.",
+                    tasks_args.project
+                );
+            }
+            for description in descriptions {
+                println!("{}", description);
+            }
+        }
+        Commands::Search { query } => {
+            let matches = entries.search(query);
+            if matches.is_empty() {
+                println!("No entries match \"{}\"", query);
+            }
+            for entry in matches {
+                print!(
+                    "{}  {}  {} ",
+                    entry.short_id(),
+                    show_time(entry.start_time()),
+                    entry
+                );
+                match entry.duration() {
+                    Some(duration) => println!("({})", format_duration(duration)),
+                    None => println!("({}, running)", format_duration(entry.elapsed())),
+                }
+            }
+        }
+        Commands::Completions { .. } => {
+            unreachable!("Completions are printed before loading entries")
+        }
+        Commands::Verify { .. } => unreachable!("Verify runs before loading entries"),
+        Commands::Heartbeat => {
+            if entries.current_entry()?.is_some() {
+                record_heartbeat(data_file)?;
+            } else {
+                exit_code = ExitCode::from(EXIT_NOTHING_TO_DO);
+            }
+        }
+        Commands::Gaps(gaps_args) => {
+            let date = gaps_args.date.unwrap_or(Local::now().date_naive());
+            let work_hours = config.work_hours.as_ref();
+            let from = gaps_args
+                .from
+                .or(work_hours.map(|hours| hours.start))
+                .unwrap_or(NaiveTime::from_hms_opt(9, 0, 0).expect("09:00 is a valid time"));
+            let to = gaps_args
+                .to
+                .or(work_hours.map(|hours| hours.end))
+                .unwrap_or(NaiveTime::from_hms_opt(17, 0, 0).expect("17:00 is a valid time"));
+            let at = |time| local_time(date.and_time(time));
+
+            let min_gap = Duration::from_secs(gaps_args.min_gap * 60);
+            let gaps = entries.gaps_in_range(at(from), at(to), min_gap);
+            if gaps.is_empty() {
+                println!("No gaps on {}", date);
+            }
+            for (start, end) in gaps {
+                let length = end.duration_since(start).unwrap_or_default();
+                println!(
+                    "{} to {}  ({})",
+                    zone.format(start, "%H:%M"),
+                    zone.format(end, "%H:%M"),
+                    format_hours_minutes(length)
+                );
+            }
+        }
+        Commands::Check => {
+            for problem in entries.validate() {
+                println!("Problem: {}", problem);
+            }
+            let overlaps = entries.find_overlaps();
+            if overlaps.is_empty() {
+                println!("No overlapping entries");
+            }
+            for (first, second) in overlaps {
+                println!("Overlap:");
+                let pair = [first, second].map(|id| entries.get_entry(id));
+                for entry in pair.into_iter().flatten() {
+                    println!(
+                        "  {}  {} ({} to {})",
+                        entry.short_id(),
+                        entry,
+                        show_time(entry.start_time()),
+                        entry.end_time().map_or("now".to_string(), show_time)
+                    );
+                }
+            }
+        }
+        Commands::Profiles => {
+            if config.profiles.is_empty() {
+                println!("No profiles configured");
+            }
+            for (name, path) in &config.profiles {
+                let marker = if args.profile.as_ref() == Some(name) { "*" } else { " " };
+                println!("{} {} = {}", marker, name, path.display());
             }
         }
     }
 
     // Save updated entries
-    entries.save("./entries.ron")?;
+    if !read_only {
+        with_progress("Saving entries...", || {
+            save_entries(&entries, data_file, config.pretty_data_file)
+        })?;
+    }
+    drop(lock);
 
-    Ok(())
+    Ok(exit_code)
+}
+
+/// Runs `verify`, checking the data file against its checksum without loading it, or recording a
+/// new checksum with `--accept`
+fn verify_data_file(
+    path: &Path,
+    accept: bool,
+    wait: bool,
+    pretty: bool,
+) -> atomichron::Result<ExitCode> {
+    let result = if accept {
+        let _lock = DataFileLock::acquire(path, wait)?;
+        EntryList::load_unverified(path)
+            .and_then(|entries| save_entries(&entries, path, pretty))
+            .map(|_| Integrity::Verified)
+    } else {
+        EntryList::verify_checksum(path)
+    };
+
+    match result {
+        Ok(Integrity::Verified) if accept => println!("Recorded a new checksum for the data file."),
+        Ok(Integrity::Verified) => println!("Data file matches its checksum."),
+        Ok(Integrity::Unchecked) => {
+            println!("No checksum recorded yet; one will be written on the next change.")
+        }
+        Err(Error::File(e)) if e.kind() == io::ErrorKind::NotFound => {
+            println!("No entries yet — run `atomichron start` to begin.")
+        }
+        Err(Error::IntegrityCheckFailed) => {
+            println!("Data file doesn't match its checksum, so it may be corrupt.");
+            println!("If you edited it by hand, `atomichron verify --accept` keeps your changes.");
+            return Ok(ExitCode::FAILURE);
+        }
+        Err(e) => return Err(e),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Applies the config's list-level settings to freshly loaded entries
+fn apply_config(entries: &mut EntryList, config: &Config) {
+    entries.set_round_on_stop(config.round_on_stop);
+    entries.set_no_project_label(config.no_project_label.clone());
+    entries.set_default_currency(config.default_currency.clone());
+    entries.set_tag_aliases(config.tag_aliases.clone());
+}
+
+fn save_entries(entries: &EntryList, path: &Path, pretty: bool) -> atomichron::Result<()> {
+    if pretty {
+        entries.save_pretty(path)
+    } else {
+        entries.save(path)
+    }
 }
@@ -0,0 +1,40 @@
+use chrono::Local;
+use std::time::Duration;
+
+use crate::{aggregate::day_range, Entry, EntryList};
+
+/// A snapshot of what's being tracked, for showing in a prompt or status bar
+#[derive(Debug, Clone)]
+pub struct Status {
+    /// The running entry, if there is one
+    pub running: Option<Entry>,
+
+    /// How long the running entry has been going, not counting pauses, or zero if nothing is running
+    pub elapsed: Duration,
+
+    /// Total time tracked on entries which started today (local time), plus the running entry's
+    /// time so far today
+    pub today: Duration,
+}
+
+impl EntryList {
+    /// Takes a [`Status`] snapshot as of now
+    pub fn status(&self) -> Status {
+        let running = self.current_entry().ok().flatten().cloned();
+        let elapsed = running.as_ref().map(Entry::elapsed).unwrap_or_default();
+        let (since, until) = day_range(Local::now().date_naive());
+        let finished: Duration = self.daily_totals(since, until, false).values().sum();
+        // A running entry may have started before today, e.g. overnight, so only today's part counts
+        let running_today = running
+            .as_ref()
+            .filter(|entry| entry.end_time().is_none())
+            .map(|entry| entry.elapsed_since(since));
+        let today = finished + running_today.unwrap_or_default();
+
+        Status {
+            running,
+            elapsed,
+            today,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+use std::{io::Read, time::SystemTime};
+use uuid::Uuid;
+
+use crate::{errors::Result, Entry, EntryBuilder, EntryList, Error};
+
+/// A row of a Toggl Track CSV export, keeping only the columns atomichron has a use for
+#[derive(Debug, Deserialize)]
+struct TogglRow {
+    #[serde(rename = "Client", default)]
+    client: String,
+    #[serde(rename = "Project", default)]
+    project: String,
+    #[serde(rename = "Description", default)]
+    description: String,
+    #[serde(rename = "Tags", default)]
+    tags: String,
+    #[serde(rename = "Billable", default)]
+    billable: String,
+    #[serde(rename = "Start date")]
+    start_date: String,
+    #[serde(rename = "Start time")]
+    start_time: String,
+    #[serde(rename = "End date")]
+    end_date: String,
+    #[serde(rename = "End time")]
+    end_time: String,
+}
+
+/// Outcome of a call to [`EntryList::import_toggl_csv`]
+#[derive(Debug, Default)]
+pub struct TogglImport {
+    /// Ids of the entries added, in the order of their rows
+    pub imported: Vec<Uuid>,
+
+    /// Rows which weren't imported, by line number (the header being line 1), along with why
+    pub skipped: Vec<(u64, Error)>,
+}
+
+impl EntryList {
+    /// Imports the rows of a CSV file exported from Toggl Track as finished entries
+    ///
+    /// The Project, Description, Tags, Start date, Start time, End date and End time columns are
+    /// used, as are Client (as the category) and Billable if they're there; other columns are
+    /// ignored. Times are read as local times, like `2024-01-15` and `09:00:00`, and tags are split
+    /// on commas. Each row gets a new id, so importing the same file twice adds its entries twice.
+    ///
+    /// Rows which can't be read, or which end before they start, are skipped and handed back in
+    /// [`TogglImport::skipped`] rather than stopping the import.
+    ///
+    /// # Errors
+    /// - Returns an error if the header row can't be read
+    pub fn import_toggl_csv<R>(&mut self, reader: R) -> Result<TogglImport>
+    where
+        R: Read,
+    {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+        let headers = csv_reader.headers()?.clone();
+
+        let mut result = TogglImport::default();
+        for record in csv_reader.records() {
+            let line = record
+                .as_ref()
+                .map_or_else(|e| e.position(), |record| record.position())
+                .map_or(0, |position| position.line());
+            let row = record
+                .map_err(Error::from)
+                .and_then(|record| Ok(record.deserialize::<TogglRow>(Some(&headers))?));
+
+            match row.and_then(toggl_entry) {
+                Ok(entry) => {
+                    result.imported.push(entry.uuid());
+                    self.entries.insert(entry.id(), entry);
+                }
+                Err(e) => result.skipped.push((line, e)),
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Builds a finished entry from a row of a Toggl export
+fn toggl_entry(row: TogglRow) -> Result<Entry> {
+    let tags = row
+        .tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut builder = EntryBuilder::new()
+        .tags(tags)
+        .start_time(toggl_time(&row.start_date, &row.start_time)?)
+        .end_time(toggl_time(&row.end_date, &row.end_time)?);
+    if !row.client.is_empty() {
+        builder = builder.category(row.client);
+    }
+    if !row.project.is_empty() {
+        builder = builder.project(row.project);
+    }
+    if !row.description.is_empty() {
+        builder = builder.description(row.description);
+    }
+    if !row.billable.is_empty() {
+        builder = builder.billable(row.billable.eq_ignore_ascii_case("yes"));
+    }
+
+    builder.build()
+}
+
+/// Reads a local date and time from the separate columns Toggl writes them in
+fn toggl_time(date: &str, time: &str) -> Result<SystemTime> {
+    let invalid = || Error::InvalidDateTime(format!("{} {}", date, time));
+
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+    let time = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time.trim(), format).ok())
+        .ok_or_else(invalid)?;
+
+    NaiveDateTime::new(date, time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(invalid)
+}
@@ -0,0 +1,66 @@
+use std::time::SystemTime;
+
+use thiserror::Error as ThisError;
+use uuid::Uuid;
+
+use crate::{errors::Result, EntryList, Error};
+
+/// A problem found in an entry list by [`EntryList::validate`], usually from a bad manual edit
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+pub enum ValidationError {
+    /// The entry's `end_time` is before its `start_time`
+    #[error("Entry {0} ends before it starts")]
+    EndBeforeStart(Uuid),
+
+    /// The entry's `start_time` hasn't happened yet
+    #[error("Entry {0} starts in the future")]
+    FutureStart(Uuid),
+
+    /// The current entry id doesn't match any entry
+    #[error("Current entry {0} is missing from the entry list")]
+    MissingCurrentEntry(Uuid),
+}
+
+impl EntryList {
+    /// Checks the list for entries ending before they start or starting in the future, and for a
+    /// current entry which doesn't exist
+    ///
+    /// A missing current entry is dropped when the file is loaded, but is still reported here.
+    /// Entries started in the future on purpose (see [`EntryList::set_allow_future_starts`]) are
+    /// reported too. Problems are given oldest entry first.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let now = SystemTime::now();
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_unstable_by_key(|entry| (entry.start_time(), entry.id()));
+
+        let mut problems = Vec::new();
+        let missing = self
+            .missing_current_entry
+            .or(self.current_entry)
+            .filter(|id| !self.entries.contains_key(id));
+        if let Some(id) = missing {
+            problems.push(ValidationError::MissingCurrentEntry(Uuid::from_bytes(id)));
+        }
+        for entry in entries {
+            if entry.end_time().is_some_and(|end| end < entry.start_time()) {
+                problems.push(ValidationError::EndBeforeStart(entry.uuid()));
+            }
+            if entry.start_time() > now {
+                problems.push(ValidationError::FutureStart(entry.uuid()));
+            }
+        }
+
+        problems
+    }
+
+    /// Like [`EntryList::validate`], but fails if any problem is found
+    ///
+    /// # Errors
+    /// - Returns an error if [`EntryList::validate`] finds any problems
+    pub fn validate_strict(&self) -> Result<()> {
+        match self.validate().len() {
+            0 => Ok(()),
+            count => Err(Error::ValidationFailed(count)),
+        }
+    }
+}
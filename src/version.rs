@@ -0,0 +1,65 @@
+use serde::Deserialize;
+use std::io::Read;
+
+use crate::{errors::Result, EntryList, Error};
+
+/// Version of the data file format written by this build
+///
+/// Bump this whenever the format changes in a way older builds can't read, and teach
+/// [`EntryList::migrate`] how to upgrade files from the previous version.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Just enough of a data file to find out which format version it's in
+///
+/// Files from before versioning have no `version` field, and count as version 0.
+#[derive(Deserialize)]
+#[serde(rename = "EntryList")]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Reads the format version of a data file, without deserializing the rest of it
+///
+/// # Errors
+/// - Returns an error if the file isn't valid RON
+/// - Returns an error if the file is from a newer format version than this build supports
+pub(crate) fn check_version<R: Read>(reader: R) -> Result<u32> {
+    let probe: VersionProbe = ron::de::from_reader(reader)?;
+    supported_version(probe.version)
+}
+
+/// Passes through a format version read from a data file, if this build can read it
+///
+/// # Errors
+/// - Returns an error if the version is newer than [`FORMAT_VERSION`]
+pub(crate) fn supported_version(version: u32) -> Result<u32> {
+    if version > FORMAT_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    Ok(version)
+}
+
+impl EntryList {
+    /// Get the format version this list will be saved in
+    pub fn format_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Upgrades a list read from a file in format version `from` to [`FORMAT_VERSION`]
+    pub(crate) fn migrate(&mut self, from: u32) {
+        // Version 0 files predate pauses, edit history and the version field itself. Serde already
+        // defaults all of those, so there's nothing else to do for them.
+        if from < 1 {
+            self.version = 1;
+        }
+        // Version 2 added split and merge records to entry history, which older builds can't read.
+        // Files from version 1 don't have any, so they're already valid.
+        if from < 2 {
+            self.version = 2;
+        }
+
+        debug_assert_eq!(self.version, FORMAT_VERSION);
+    }
+}
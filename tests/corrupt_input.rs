@@ -0,0 +1,123 @@
+use std::{fs, path::PathBuf};
+
+use atomichron::{EntryList, Error};
+
+/// Minimal xorshift generator, so failures are reproducible without extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+/// Writes bytes to a scratch file unique to this test, and returns its path
+fn scratch_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "atomichron-{}-{}.ron",
+        name,
+        std::process::id()
+    ));
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+/// A valid file with one finished entry and one running entry
+fn valid_file() -> Vec<u8> {
+    let mut entries = EntryList::new();
+    entries
+        .start_entry(Some("project".into()), None, vec!["tag".into()], None, true)
+        .unwrap();
+    entries
+        .start_entry(None, Some("running".into()), Vec::new(), None, true)
+        .unwrap();
+
+    let path = scratch_file("valid", b"");
+    entries.save(&path).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    fs::remove_file(path).unwrap();
+    bytes
+}
+
+#[test]
+fn random_bytes_are_rejected() {
+    let mut rng = Rng(0x5eed);
+
+    for i in 0..500 {
+        let len = (rng.next() % 512) as usize;
+        let path = scratch_file("random", &rng.bytes(len));
+
+        assert!(EntryList::load(&path).is_err(), "garbage input {} loaded", i);
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn mutated_files_never_panic() {
+    let valid = valid_file();
+    let mut rng = Rng(0xc0ffee);
+
+    for _ in 0..500 {
+        let mut bytes = valid.clone();
+        match rng.next() % 3 {
+            0 => bytes.truncate((rng.next() as usize) % bytes.len()),
+            1 => {
+                let index = (rng.next() as usize) % bytes.len();
+                bytes[index] = rng.next() as u8;
+            }
+            _ => {
+                let index = (rng.next() as usize) % bytes.len();
+                bytes.remove(index);
+            }
+        }
+        let path = scratch_file("mutated", &bytes);
+
+        // Whatever loads must be usable without panicking
+        if let Ok(mut entries) = EntryList::load(&path) {
+            entries.current_entry().unwrap();
+            entries.get_entries_in_order(true);
+            entries
+                .stop_current_entry(None, None, Vec::new(), None)
+                .unwrap();
+            entries.clear_current_entry().unwrap();
+        }
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn dangling_current_entry_is_dropped() {
+    let mut entries = EntryList::new();
+    entries
+        .start_entry(None, None, Vec::new(), None, true)
+        .unwrap();
+    let id = entries.current_entry().unwrap().unwrap().id();
+
+    // Point the current entry at an id which isn't in the list
+    let path = scratch_file("dangling", b"");
+    entries.save(&path).unwrap();
+    let mut contents = fs::read_to_string(&path).unwrap();
+    let id_text = format!("{:?}", id)
+        .trim_matches(|c| c == '[' || c == ']')
+        .replace(' ', "");
+    let current = contents.rfind(&id_text).unwrap();
+    contents.replace_range(current..current + id_text.len(), &vec!["0"; 16].join(","));
+    fs::write(&path, contents).unwrap();
+
+    // A hand edit fails the checksum check unless it's accepted
+    assert!(matches!(
+        EntryList::load(&path),
+        Err(Error::IntegrityCheckFailed)
+    ));
+    let entries = EntryList::load_unverified(&path).unwrap();
+    assert!(entries.current_entry().unwrap().is_none());
+    assert_eq!(entries.len(), 1);
+    fs::remove_file(path).unwrap();
+}
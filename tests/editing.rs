@@ -0,0 +1,156 @@
+use std::time::{Duration, SystemTime};
+
+use atomichron::{EntryList, Error, FieldChange, OnConflict};
+use uuid::Uuid;
+
+fn hours_ago(hours: u64) -> SystemTime {
+    SystemTime::now() - Duration::from_secs(hours * 3600)
+}
+
+/// Adds a finished entry running between the given numbers of hours ago, and returns its id
+fn add(entries: &mut EntryList, from: u64, to: u64) -> Uuid {
+    entries
+        .add_entry(hours_ago(from), hours_ago(to), None, None, Vec::new(), None)
+        .unwrap()
+        .uuid()
+}
+
+#[test]
+fn latest_entry_can_be_reopened() {
+    let mut entries = EntryList::new();
+    add(&mut entries, 5, 4);
+    let latest = add(&mut entries, 3, 2);
+
+    let reopened = entries.reopen_entry(latest).unwrap();
+    assert_eq!(reopened.end_time(), None);
+    assert_eq!(entries.current_entry_id(), Some(latest));
+}
+
+#[test]
+fn reopening_is_refused_when_it_would_overlap() {
+    let mut entries = EntryList::new();
+    let earlier = add(&mut entries, 5, 4);
+    let later = add(&mut entries, 3, 2);
+
+    assert!(matches!(
+        entries.reopen_entry(earlier),
+        Err(Error::ReopenWouldOverlap(id)) if id == later
+    ));
+    assert!(entries.get_entry(earlier).unwrap().end_time().is_some());
+    assert_eq!(entries.current_entry_id(), None);
+}
+
+#[test]
+fn split_must_fall_strictly_inside_the_entry() {
+    let mut entries = EntryList::new();
+    let id = add(&mut entries, 4, 2);
+    let entry = entries.get_entry(id).unwrap();
+    let (start, end) = (entry.start_time(), entry.end_time().unwrap());
+
+    for at in [start, end, hours_ago(5), hours_ago(1)] {
+        assert!(matches!(
+            entries.split_entry(id, at),
+            Err(Error::SplitOutsideEntry)
+        ));
+    }
+    assert_eq!(entries.len(), 1);
+
+    let (first, second) = entries.split_entry(id, hours_ago(3)).unwrap();
+    let (first, second) = (
+        entries.get_entry(first).unwrap(),
+        entries.get_entry(second).unwrap(),
+    );
+    assert_eq!(first.start_time(), start);
+    assert_eq!(first.end_time(), Some(second.start_time()));
+    assert_eq!(second.end_time(), Some(end));
+    assert_eq!(entries.len(), 2);
+}
+
+#[test]
+fn split_keeps_history() {
+    let mut entries = EntryList::new();
+    let id = add(&mut entries, 4, 2);
+    entries
+        .edit_entry(id, Some("renamed".into()), None, None, None, None)
+        .unwrap();
+    let at = hours_ago(3);
+
+    let (first, second) = entries.split_entry(id, at).unwrap();
+    for half in [first, second] {
+        let history = entries.get_entry(half).unwrap().history();
+        assert!(matches!(history[0].change, FieldChange::Project(None, _)));
+        assert_eq!(
+            history.last().unwrap().change,
+            FieldChange::Split(*id.as_bytes(), at)
+        );
+    }
+}
+
+#[test]
+fn non_adjacent_entries_merge_across_the_gap() {
+    let mut entries = EntryList::new();
+    let earlier = add(&mut entries, 6, 5);
+    let later = add(&mut entries, 3, 2);
+    let start = entries.get_entry(earlier).unwrap().start_time();
+    let end = entries.get_entry(later).unwrap().end_time();
+
+    // Passing the later entry first still spans from the earlier start to the later end
+    let merged = entries.merge_entries(later, earlier).unwrap();
+    let merged = entries.get_entry(merged).unwrap();
+    assert_eq!(merged.start_time(), start);
+    assert_eq!(merged.end_time(), end);
+    // The hour between them counts too, on top of the two hours tracked
+    assert!(merged.duration().unwrap() > Duration::from_secs(3 * 3600));
+    assert_eq!(entries.len(), 1);
+
+    assert!(matches!(
+        entries.merge_entries(later, earlier),
+        Err(Error::EntryNotFound(_))
+    ));
+}
+
+#[test]
+fn merge_keeps_both_histories() {
+    let mut entries = EntryList::new();
+    let a = add(&mut entries, 4, 3);
+    let b = add(&mut entries, 3, 2);
+    entries
+        .edit_entry(a, Some("a".into()), None, None, None, None)
+        .unwrap();
+    entries
+        .edit_entry(b, None, Some("b".into()), None, None, None)
+        .unwrap();
+
+    let merged = entries.merge_entries(a, b).unwrap();
+    let history = entries.get_entry(merged).unwrap().history();
+    assert_eq!(history.len(), 3);
+    assert!(matches!(history[0].change, FieldChange::Project(..)));
+    assert!(matches!(history[1].change, FieldChange::Description(..)));
+    assert_eq!(
+        history[2].change,
+        FieldChange::Merge(*a.as_bytes(), *b.as_bytes())
+    );
+}
+
+#[test]
+fn importing_merged_tags_keeps_history() {
+    let mut entries = EntryList::new();
+    let id = add(&mut entries, 2, 1);
+
+    // The same entry, tagged elsewhere
+    let mut other = EntryList::new();
+    let copy = entries.get_entry(id).unwrap().clone();
+    other.import_entries(vec![copy], OnConflict::Skip).unwrap();
+    let tagged = other.add_tags(id, vec!["new".into()]).unwrap().clone();
+
+    let summary = entries
+        .import_entries(vec![tagged], OnConflict::MergeTags)
+        .unwrap();
+    assert_eq!(summary.updated, 1);
+    let entry = entries.get_entry(id).unwrap();
+    assert_eq!(entry.tags(), &["new".to_string()]);
+    assert_eq!(
+        entry.history().last().unwrap().change,
+        FieldChange::Tags(Vec::new(), vec!["new".into()])
+    );
+}
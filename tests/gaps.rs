@@ -0,0 +1,67 @@
+use std::time::{Duration, SystemTime};
+
+use atomichron::EntryList;
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// Adds a finished entry running between the given numbers of hours before `now`
+fn add(entries: &mut EntryList, now: SystemTime, from: u32, to: u32) {
+    entries
+        .add_entry(
+            now - HOUR * from,
+            now - HOUR * to,
+            None,
+            None,
+            Vec::new(),
+            None,
+        )
+        .unwrap();
+}
+
+#[test]
+fn overlapping_entries_cover_time_together() {
+    let now = SystemTime::now();
+    let mut entries = EntryList::new();
+    add(&mut entries, now, 10, 4);
+    // Both inside the long entry, so the time between them isn't a gap
+    add(&mut entries, now, 9, 8);
+    add(&mut entries, now, 6, 5);
+    add(&mut entries, now, 3, 2);
+
+    assert_eq!(
+        entries.gaps_in_range(now - HOUR * 12, now - HOUR, Duration::ZERO),
+        vec![
+            (now - HOUR * 12, now - HOUR * 10),
+            (now - HOUR * 4, now - HOUR * 3),
+            (now - HOUR * 2, now - HOUR),
+        ]
+    );
+}
+
+#[test]
+fn short_gaps_are_left_out() {
+    let now = SystemTime::now();
+    let mut entries = EntryList::new();
+    add(&mut entries, now, 6, 5);
+    add(&mut entries, now, 4, 3);
+
+    // The hour between the entries isn't longer than the minimum, the two at the edges are
+    assert_eq!(
+        entries.gaps_in_range(now - HOUR * 8, now - HOUR, HOUR),
+        vec![
+            (now - HOUR * 8, now - HOUR * 6),
+            (now - HOUR * 3, now - HOUR)
+        ]
+    );
+}
+
+#[test]
+fn nothing_after_now_is_a_gap() {
+    let now = SystemTime::now();
+    let entries = EntryList::new();
+
+    let gaps = entries.gaps_in_range(now - HOUR, now + HOUR * 5, Duration::ZERO);
+    assert_eq!(gaps.len(), 1);
+    assert_eq!(gaps[0].0, now - HOUR);
+    assert!(gaps[0].1 < now + HOUR);
+}
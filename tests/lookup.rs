@@ -0,0 +1,93 @@
+use std::time::{Duration, SystemTime};
+
+use atomichron::{EntryList, Error};
+use uuid::Uuid;
+
+/// Adds the given number of finished entries, an hour apart, and returns their ids
+fn add_entries(entries: &mut EntryList, count: u64) -> Vec<Uuid> {
+    (1..=count)
+        .map(|hour| {
+            let end = SystemTime::now() - Duration::from_secs(hour * 3600);
+            entries
+                .add_entry(
+                    end - Duration::from_secs(60),
+                    end,
+                    None,
+                    None,
+                    Vec::new(),
+                    None,
+                )
+                .unwrap()
+                .uuid()
+        })
+        .collect()
+}
+
+#[test]
+fn unique_prefix_finds_the_entry() {
+    let mut entries = EntryList::new();
+    let ids = add_entries(&mut entries, 3);
+
+    for id in ids {
+        let simple = id.simple().to_string();
+        assert_eq!(entries.find_by_prefix(&simple).unwrap().uuid(), id);
+        // Case and hyphens don't matter
+        let hyphenated = id.hyphenated().to_string().to_ascii_uppercase();
+        assert_eq!(entries.find_by_prefix(&hyphenated).unwrap().uuid(), id);
+    }
+}
+
+#[test]
+fn shared_prefix_is_ambiguous() {
+    let mut entries = EntryList::new();
+    // With more entries than hex digits, at least two ids start with the same digit
+    let ids = add_entries(&mut entries, 17);
+    let shared = (0..16)
+        .map(|digit| format!("{:x}", digit))
+        .find(|digit| {
+            ids.iter()
+                .filter(|id| id.simple().to_string().starts_with(digit.as_str()))
+                .count()
+                > 1
+        })
+        .unwrap();
+
+    assert!(matches!(
+        entries.find_by_prefix(&shared),
+        Err(Error::AmbiguousId(prefix)) if prefix == shared
+    ));
+}
+
+#[test]
+fn unused_prefix_matches_nothing() {
+    let mut entries = EntryList::new();
+    let ids = add_entries(&mut entries, 3);
+    let unused = (0..16)
+        .map(|digit| format!("{:x}", digit))
+        .find(|digit| {
+            !ids.iter()
+                .any(|id| id.simple().to_string().starts_with(digit.as_str()))
+        })
+        .unwrap();
+
+    assert!(matches!(
+        entries.find_by_prefix(&unused),
+        Err(Error::NoMatchingId(prefix)) if prefix == unused
+    ));
+    assert!(matches!(
+        EntryList::new().find_by_prefix("a"),
+        Err(Error::NoMatchingId(_))
+    ));
+}
+
+#[test]
+fn prefix_must_be_hex() {
+    let entries = EntryList::new();
+
+    for prefix in ["", "-", "xyz", "12g"] {
+        assert!(matches!(
+            entries.find_by_prefix(prefix),
+            Err(Error::InvalidId(_))
+        ));
+    }
+}
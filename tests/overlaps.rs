@@ -0,0 +1,69 @@
+use std::time::{Duration, SystemTime};
+
+use atomichron::EntryList;
+use uuid::Uuid;
+
+/// Adds a finished entry running between the given numbers of hours before `now`, and returns its id
+fn add(entries: &mut EntryList, now: SystemTime, from: u64, to: u64) -> Uuid {
+    let hours_before = |hours: u64| now - Duration::from_secs(hours * 3600);
+    entries
+        .add_entry(
+            hours_before(from),
+            hours_before(to),
+            None,
+            None,
+            Vec::new(),
+            None,
+        )
+        .unwrap()
+        .uuid()
+}
+
+#[test]
+fn long_entry_overlaps_everything_inside_it() {
+    let now = SystemTime::now();
+    let mut entries = EntryList::new();
+    let long = add(&mut entries, now, 10, 2);
+    let first = add(&mut entries, now, 8, 7);
+    let second = add(&mut entries, now, 5, 4);
+    // Starting exactly as the second one ends is touching, not overlapping
+    let third = add(&mut entries, now, 4, 3);
+
+    assert_eq!(
+        entries.find_overlaps(),
+        vec![(long, first), (long, second), (long, third)]
+    );
+}
+
+#[test]
+fn touching_and_empty_entries_never_overlap() {
+    let now = SystemTime::now();
+    let mut entries = EntryList::new();
+    add(&mut entries, now, 6, 5);
+    add(&mut entries, now, 5, 4);
+    add(&mut entries, now, 4, 3);
+    // Lasts no time at all, right in the middle of the entry before it
+    add(&mut entries, now, 5, 5);
+
+    assert_eq!(entries.find_overlaps(), Vec::new());
+}
+
+#[test]
+fn running_entry_overlaps_until_now() {
+    let now = SystemTime::now();
+    let mut entries = EntryList::new();
+    let running = entries
+        .start_entry_at(
+            now - Duration::from_secs(3 * 3600),
+            None,
+            None,
+            Vec::new(),
+            None,
+            false,
+        )
+        .unwrap()
+        .started;
+    let finished = add(&mut entries, now, 2, 1);
+
+    assert_eq!(entries.find_overlaps(), vec![(running, finished)]);
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, SystemTime};
+
+use atomichron::EntryList;
+
+#[test]
+fn end_time_is_untouched_without_policy() {
+    let mut entries = EntryList::new();
+    entries
+        .start_entry(None, None, Vec::new(), None, true)
+        .unwrap();
+
+    let before = SystemTime::now();
+    let (entry, _) = entries
+        .stop_current_entry(None, None, Vec::new(), None)
+        .unwrap()
+        .unwrap();
+    let after = SystemTime::now();
+
+    let end_time = entry.end_time().unwrap();
+    assert!(before <= end_time && end_time <= after);
+}
+
+#[test]
+fn end_time_is_rounded_with_policy() {
+    let increment = Duration::from_secs(15 * 60);
+
+    let mut entries = EntryList::new();
+    entries.set_round_on_stop(Some(increment));
+    entries
+        .start_entry(None, None, Vec::new(), None, true)
+        .unwrap();
+    let (entry, _) = entries
+        .stop_current_entry(None, None, Vec::new(), None)
+        .unwrap()
+        .unwrap();
+
+    let duration = entry
+        .end_time()
+        .unwrap()
+        .duration_since(entry.start_time())
+        .unwrap();
+    assert_eq!(duration.as_nanos() % increment.as_nanos(), 0);
+    assert!(duration <= increment);
+}
@@ -0,0 +1,94 @@
+use std::fs;
+
+use atomichron::{Entry, EntryList, Error, Integrity};
+
+/// A list with a finished, tagged entry and a running entry
+fn sample_list() -> EntryList {
+    let mut entries = EntryList::new();
+    entries
+        .start_entry(
+            Some("project".into()),
+            Some("finished".into()),
+            vec!["tag".into()],
+            Some("category".into()),
+            true,
+        )
+        .unwrap();
+    entries
+        .start_entry(None, Some("running".into()), Vec::new(), None, true)
+        .unwrap();
+    entries
+}
+
+#[test]
+fn string_round_trip_keeps_everything() {
+    let entries = sample_list();
+    let ron = entries.to_ron_string().unwrap();
+    let loaded = EntryList::from_ron_str(&ron).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.current_entry_id(), entries.current_entry_id());
+    for entry in &entries {
+        let copy = loaded.get_entry(entry.uuid()).unwrap();
+        assert_eq!(copy.to_string(), entry.to_string());
+        assert_eq!(copy.start_time(), entry.start_time());
+        assert_eq!(copy.end_time(), entry.end_time());
+    }
+    assert_eq!(loaded.to_ron_string().unwrap(), ron);
+}
+
+#[test]
+fn string_matches_saved_file() {
+    let entries = sample_list();
+    let path =
+        std::env::temp_dir().join(format!("atomichron-round-trip-{}.ron", std::process::id()));
+    entries.save(&path).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        entries.to_ron_string().unwrap()
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn edits_fail_the_checksum_until_accepted() {
+    let ron = sample_list().to_ron_string().unwrap();
+    let edited = ron.replacen("finished", "edited", 1);
+    assert!(matches!(
+        EntryList::from_ron_str(&edited),
+        Err(Error::IntegrityCheckFailed)
+    ));
+
+    let path = std::env::temp_dir().join(format!("atomichron-edited-{}.ron", std::process::id()));
+    fs::write(&path, edited).unwrap();
+    assert!(matches!(
+        EntryList::verify_checksum(&path),
+        Err(Error::IntegrityCheckFailed)
+    ));
+
+    // Saving what was loaded without the check records a new checksum, pretty or not
+    EntryList::load_unverified(&path)
+        .unwrap()
+        .save_pretty(&path)
+        .unwrap();
+    assert_eq!(
+        EntryList::verify_checksum(&path).unwrap(),
+        Integrity::Verified
+    );
+    let loaded = EntryList::load(&path).unwrap();
+    let edited = |entry: &Entry| entry.description().as_deref() == Some("edited");
+    assert!(loaded.iter().any(edited));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let ron = "(version: 9999, entries: {}, current_entry: None, future_field: 1)";
+
+    assert!(matches!(
+        EntryList::from_ron_str(ron),
+        Err(Error::UnsupportedVersion(9999))
+    ));
+    assert!(EntryList::from_ron_str("not ron").is_err());
+}
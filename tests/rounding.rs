@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use atomichron::{parse_increment, round_duration, RoundMode};
+
+const INCREMENT: Duration = Duration::from_secs(15 * 60);
+
+fn minutes(minutes: f64) -> Duration {
+    Duration::from_secs_f64(minutes * 60.0)
+}
+
+#[test]
+fn multiples_are_unchanged() {
+    for mode in [RoundMode::Up, RoundMode::Down, RoundMode::Nearest] {
+        assert_eq!(round_duration(minutes(0.0), INCREMENT, mode), minutes(0.0));
+        assert_eq!(
+            round_duration(minutes(15.0), INCREMENT, mode),
+            minutes(15.0)
+        );
+        assert_eq!(
+            round_duration(minutes(45.0), INCREMENT, mode),
+            minutes(45.0)
+        );
+    }
+}
+
+#[test]
+fn up_rounds_any_started_block_up() {
+    assert_eq!(
+        round_duration(Duration::from_nanos(1), INCREMENT, RoundMode::Up),
+        minutes(15.0)
+    );
+    assert_eq!(
+        round_duration(minutes(7.5), INCREMENT, RoundMode::Up),
+        minutes(15.0)
+    );
+    assert_eq!(
+        round_duration(
+            minutes(15.0) + Duration::from_nanos(1),
+            INCREMENT,
+            RoundMode::Up
+        ),
+        minutes(30.0)
+    );
+}
+
+#[test]
+fn down_drops_any_partial_block() {
+    assert_eq!(
+        round_duration(
+            minutes(15.0) - Duration::from_nanos(1),
+            INCREMENT,
+            RoundMode::Down
+        ),
+        minutes(0.0)
+    );
+    assert_eq!(
+        round_duration(minutes(7.5), INCREMENT, RoundMode::Down),
+        minutes(0.0)
+    );
+    assert_eq!(
+        round_duration(minutes(22.5), INCREMENT, RoundMode::Down),
+        minutes(15.0)
+    );
+}
+
+#[test]
+fn nearest_rounds_halfway_up() {
+    assert_eq!(
+        round_duration(minutes(7.5), INCREMENT, RoundMode::Nearest),
+        minutes(15.0)
+    );
+    assert_eq!(
+        round_duration(
+            minutes(7.5) - Duration::from_nanos(1),
+            INCREMENT,
+            RoundMode::Nearest
+        ),
+        minutes(0.0)
+    );
+    assert_eq!(
+        round_duration(minutes(22.5), INCREMENT, RoundMode::Nearest),
+        minutes(30.0)
+    );
+    assert_eq!(
+        round_duration(minutes(22.0), INCREMENT, RoundMode::Nearest),
+        minutes(15.0)
+    );
+}
+
+#[test]
+fn nearest_handles_odd_increments() {
+    let increment = Duration::from_nanos(3);
+    let round = |nanos| round_duration(Duration::from_nanos(nanos), increment, RoundMode::Nearest);
+
+    assert_eq!(round(1), Duration::from_nanos(0));
+    assert_eq!(round(2), Duration::from_nanos(3));
+    assert_eq!(round(4), Duration::from_nanos(3));
+    assert_eq!(round(5), Duration::from_nanos(6));
+}
+
+#[test]
+fn zero_increment_leaves_duration_alone() {
+    for mode in [RoundMode::Up, RoundMode::Down, RoundMode::Nearest] {
+        assert_eq!(
+            round_duration(minutes(7.5), Duration::ZERO, mode),
+            minutes(7.5)
+        );
+    }
+}
+
+#[test]
+fn increments_parse_with_units() {
+    assert_eq!(parse_increment("15"), Ok(minutes(15.0)));
+    assert_eq!(parse_increment("15m"), Ok(minutes(15.0)));
+    assert_eq!(parse_increment("1h"), Ok(minutes(60.0)));
+    assert_eq!(parse_increment("90s"), Ok(minutes(1.5)));
+    assert!(parse_increment("0m").is_err());
+    assert!(parse_increment("h").is_err());
+}
+
+#[test]
+fn oversized_increment_is_an_error() {
+    assert!(parse_increment("999999999999999999h").is_err());
+    assert!(parse_increment(&format!("{}s", u64::MAX)).is_ok());
+    assert!(parse_increment(&format!("{}m", u64::MAX)).is_err());
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use atomichron::EntryList;
+
+const HEADER: &str = "User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount ()\n";
+
+#[test]
+fn rows_become_finished_entries() {
+    let csv = format!(
+        "{}{}",
+        HEADER,
+        "Jo,jo@example.com,Acme,Website,,Fix header,No,2024-01-15,09:00:00,2024-01-15,10:30:00,01:30:00,\"design, frontend\",\n"
+    );
+
+    let mut entries = EntryList::new();
+    let import = entries.import_toggl_csv(csv.as_bytes()).unwrap();
+    assert!(import.skipped.is_empty());
+    assert_eq!(import.imported.len(), 1);
+
+    let entry = entries.get_entry(import.imported[0]).unwrap();
+    assert_eq!(entry.project().as_deref(), Some("Website"));
+    assert_eq!(entry.description().as_deref(), Some("Fix header"));
+    assert_eq!(entry.category().as_deref(), Some("Acme"));
+    assert_eq!(*entry.tags(), ["design", "frontend"]);
+    assert!(!entry.billable());
+    assert_eq!(entry.duration(), Some(Duration::from_secs(90 * 60)));
+    assert!(entries.current_entry().unwrap().is_none());
+}
+
+#[test]
+fn bad_rows_are_skipped_with_their_line() {
+    let csv = format!(
+        "{}{}{}",
+        HEADER,
+        "Jo,jo@example.com,,Internal,,Broken,No,2024-01-15,later,2024-01-15,10:45:00,,,\n",
+        "Jo,jo@example.com,,Internal,,Standup,No,2024-01-15,10:30:00,2024-01-15,10:45:00,,meeting,\n"
+    );
+
+    let mut entries = EntryList::new();
+    let import = entries.import_toggl_csv(csv.as_bytes()).unwrap();
+    assert_eq!(import.imported.len(), 1);
+    assert_eq!(import.skipped.len(), 1);
+    assert_eq!(import.skipped[0].0, 2);
+    assert_eq!(entries.len(), 1);
+}